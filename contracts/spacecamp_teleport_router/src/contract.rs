@@ -1,23 +1,35 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
-use cosmwasm_std::{from_binary, to_binary, Addr, Api, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QueryRequest, Response, StdError, StdResult, Uint128, WasmMsg, WasmQuery, BankMsg, QuerierWrapper};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Api, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    QuerierWrapper, Response, StdError, StdResult, Uint128, WasmMsg,
+};
 
-use crate::operations::execute_swap_operation;
-use crate::querier::compute_tax;
 use crate::state::{Config, CONFIG};
 
 use cw20::{Cw20ReceiveMsg, Cw20ExecuteMsg};
-use std::collections::HashMap;
-use terra_cosmwasm::{SwapResponse, TerraMsgWrapper, TerraQuerier};
-use terraswap::asset::{Asset, AssetInfo, PairInfo};
-use terraswap::pair::{QueryMsg as PairQueryMsg, SimulationResponse};
-use terraswap::querier::query_pair_info;
+use terra_cosmwasm::TerraMsgWrapper;
+use terraswap::asset::{Asset, AssetInfo};
 use terraswap::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, SwapOperation,
+};
+use terraswap::routing::{
+    assert_minimum_receive, assert_operations, execute_swap_operation, execute_swap_operations,
+    optional_addr_validate, simulate_swap_operations, swap_operation_msgs, RouterExtension,
 };
 
+/// The teleport router swaps on terraswap pairs and the market only,
+/// pools and bridges are not supported yet
+pub struct TeleportRouter;
+
+impl RouterExtension for TeleportRouter {
+    fn terraswap_factory(&self, deps: Deps) -> StdResult<Addr> {
+        let config: Config = CONFIG.load(deps.storage)?;
+        deps.api.addr_humanize(&config.terraswap_factory)
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -48,6 +60,7 @@ pub fn execute(
             operations,
             minimum_receive,
             to,
+            max_spread,
         } => {
             let api = deps.api;
             execute_swap_operations(
@@ -57,16 +70,23 @@ pub fn execute(
                 operations,
                 minimum_receive,
                 optional_addr_validate(api, to)?,
+                max_spread,
             )
         }
-        ExecuteMsg::ExecuteSwapOperation { operation, to } => {
+        ExecuteMsg::ExecuteSwapOperation {
+            operation,
+            to,
+            offer_amount,
+        } => {
             let api = deps.api;
             execute_swap_operation(
                 deps,
                 env,
                 info,
+                &TeleportRouter,
                 operation,
                 optional_addr_validate(api, to)?.map(|v| v.to_string()),
+                offer_amount,
             )
         }
         ExecuteMsg::AssertMinimumReceive {
@@ -74,7 +94,7 @@ pub fn execute(
             prev_balance,
             minimum_receive,
             receiver,
-        } => assert_minium_receive(
+        } => assert_minimum_receive(
             deps.as_ref(),
             asset_info,
             prev_balance,
@@ -106,13 +126,16 @@ pub fn execute(
             receiver,
             ref_fee_pct,
             ref_address,
-        } => execute_send_from_self_with_fee(deps,
-                                             env,
-                                             asset_info,
-                                             prev_balance,
-                                             receiver,
-                                             ref_fee_pct,
-                                             ref_address)
+        } => execute_send_from_self_with_fee(
+            deps,
+            env,
+            asset_info,
+            prev_balance,
+            receiver,
+            ref_fee_pct,
+            ref_address,
+        ),
+        _ => Err(StdError::generic_err("not implemented")),
     }
 }
 
@@ -184,16 +207,6 @@ fn create_transfer_msg(
     };
 }
 
-fn optional_addr_validate(api: &dyn Api, addr: Option<String>) -> StdResult<Option<Addr>> {
-    let addr = if let Some(addr) = addr {
-        Some(api.addr_validate(&addr)?)
-    } else {
-        None
-    };
-
-    Ok(addr)
-}
-
 pub fn receive_cw20(
     deps: DepsMut,
     env: Env,
@@ -206,6 +219,7 @@ pub fn receive_cw20(
             operations,
             minimum_receive,
             to,
+            max_spread,
         } => {
             let api = deps.api;
             execute_swap_operations(
@@ -215,6 +229,7 @@ pub fn receive_cw20(
                 operations,
                 minimum_receive,
                 optional_addr_validate(api, to)?,
+                max_spread,
             )
         }
         Cw20HookMsg::ExecuteTeleport {
@@ -236,67 +251,10 @@ pub fn receive_cw20(
                 ref_fee_pct,
             )
         }
+        _ => Err(StdError::generic_err("not implemented")),
     }
 }
 
-pub fn execute_swap_operations(
-    deps: DepsMut,
-    env: Env,
-    sender: Addr,
-    operations: Vec<SwapOperation>,
-    minimum_receive: Option<Uint128>,
-    to: Option<Addr>,
-) -> StdResult<Response<TerraMsgWrapper>> {
-    let operations_len = operations.len();
-    if operations_len == 0 {
-        return Err(StdError::generic_err("must provide operations"));
-    }
-
-    // Assert the operations are properly set
-    assert_operations(&operations)?;
-
-    let to = if let Some(to) = to { to } else { sender };
-    let target_asset_info = operations.last().unwrap().get_target_asset_info();
-
-    let mut operation_index = 0;
-    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = operations
-        .into_iter()
-        .map(|op| {
-            operation_index += 1;
-            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: op,
-                    to: if operation_index == operations_len {
-                        Some(to.to_string())
-                    } else {
-                        None
-                    },
-                })?,
-            }))
-        })
-        .collect::<StdResult<Vec<CosmosMsg<TerraMsgWrapper>>>>()?;
-
-    // Execute minimum amount assertion
-    if let Some(minimum_receive) = minimum_receive {
-        let receiver_balance = target_asset_info.query_pool(&deps.querier, deps.api, to.clone())?;
-
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: env.contract.address.to_string(),
-            funds: vec![],
-            msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
-                asset_info: target_asset_info,
-                prev_balance: receiver_balance,
-                minimum_receive,
-                receiver: to.to_string(),
-            })?,
-        }))
-    }
-
-    Ok(Response::new().add_messages(messages))
-}
-
 pub fn execute_teleport_operations(
     deps: DepsMut,
     env: Env,
@@ -313,15 +271,18 @@ pub fn execute_teleport_operations(
     }
 
     // Assert the operations are properly set
-    let swaps: Vec<SwapOperation> = operations.iter().cloned().filter(
-        |o| match o {
-            SwapOperation::NativeSwap { .. } => true,
-            SwapOperation::TerraSwap { .. } => true,
-            _ => false
+    for (index, operation) in operations.iter().enumerate() {
+        if !matches!(
+            operation,
+            SwapOperation::NativeSwap { .. } | SwapOperation::TerraSwap { .. }
+        ) {
+            return Err(StdError::generic_err(format!(
+                "invalid operations; operation {} is not supported by the teleport router",
+                index
+            )));
         }
-    ).collect();
-    // assert_operations(&operations)?;
-    assert_operations(&swaps)?;
+    }
+    assert_operations(&operations)?;
 
     let referral_is_active = check_referral_params_valid(ref_fee_pct.clone(),
                                                          ref_address.clone())?;
@@ -329,35 +290,18 @@ pub fn execute_teleport_operations(
     let have_valid_bridge_operation = bridge_operation.is_some();
 
     let to = if let Some(to) = to { to } else { sender };
-    let target_asset_info = swaps.last().unwrap().get_target_asset_info();
-
-    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
-
-    let mut operation_index = 0;
-    let swaps_len = swaps.len();
-    let mut swap_messages: Vec<CosmosMsg<TerraMsgWrapper>> = swaps
-        .into_iter()
-        .map(|op| {
-            operation_index += 1;
-            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: op,
-                    to: if operation_index == swaps_len {
-                        if referral_is_active == true || have_valid_bridge_operation == true {
-                            None // for sending referral or bridge, we should keep the output in contract
-                        } else {
-                            Some(to.to_string())
-                        }
-                    } else {
-                        None
-                    },
-                })?,
-            }))
-        })
-        .collect::<StdResult<Vec<CosmosMsg<TerraMsgWrapper>>>>()?;
-    messages.append(&mut swap_messages);
+    let target_asset_info = operations.last().unwrap().get_target_asset_info()?;
+
+    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = swap_operation_msgs(
+        &env,
+        operations,
+        None,
+        if referral_is_active == true || have_valid_bridge_operation == true {
+            None // for sending referral or bridge, we should keep the output in contract
+        } else {
+            Some(&to)
+        },
+    )?;
 
     // check for Terra Bridge operation
     // let (wallet_address_on_target_chain, bridge_contract_address_on_terra) = match bridge_operation {
@@ -452,26 +396,6 @@ fn check_for_valid_bridge_operation(operations: &Vec<SwapOperation>, api: &dyn A
 }
 
 
-fn assert_minium_receive(
-    deps: Deps,
-    asset_info: AssetInfo,
-    prev_balance: Uint128,
-    minium_receive: Uint128,
-    receiver: Addr,
-) -> StdResult<Response<TerraMsgWrapper>> {
-    let receiver_balance = asset_info.query_pool(&deps.querier, deps.api, receiver)?;
-    let swap_amount = receiver_balance.checked_sub(prev_balance)?;
-
-    if swap_amount < minium_receive {
-        return Err(StdError::generic_err(format!(
-            "assertion failed; minimum receive amount: {}, swap amount: {}",
-            minium_receive, swap_amount
-        )));
-    }
-
-    Ok(Response::default())
-}
-
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -479,7 +403,13 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::SimulateSwapOperations {
             offer_amount,
             operations,
-        } => to_binary(&simulate_swap_operations(deps, offer_amount, operations)?),
+        } => to_binary(&simulate_swap_operations(
+            deps,
+            &TeleportRouter,
+            offer_amount,
+            operations,
+        )?),
+        _ => Err(StdError::generic_err("not implemented")),
     }
 }
 
@@ -490,235 +420,10 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
             .api
             .addr_humanize(&state.terraswap_factory)?
             .to_string(),
+        // the teleport router has no owner, pools are only allowed by the terraswap router
+        owner: None,
+        ownership_proposal: None,
     };
 
     Ok(resp)
 }
-
-fn simulate_swap_operations(
-    deps: Deps,
-    offer_amount: Uint128,
-    operations: Vec<SwapOperation>,
-) -> StdResult<SimulateSwapOperationsResponse> {
-    let config: Config = CONFIG.load(deps.storage)?;
-    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
-    let terra_querier = TerraQuerier::new(&deps.querier);
-
-    let operations_len = operations.len();
-    if operations_len == 0 {
-        return Err(StdError::generic_err("must provide operations"));
-    }
-
-    let mut operation_index = 0;
-    let mut offer_amount = offer_amount;
-    for operation in operations.into_iter() {
-        operation_index += 1;
-
-        match operation {
-            SwapOperation::NativeSwap {
-                offer_denom,
-                ask_denom,
-            } => {
-                // Deduct tax before query simulation
-                // because last swap is swap_send
-                if operation_index == operations_len {
-                    offer_amount = offer_amount.checked_sub(compute_tax(
-                        &deps.querier,
-                        offer_amount,
-                        offer_denom.clone(),
-                    )?)?;
-                }
-
-                let res: SwapResponse = terra_querier.query_swap(
-                    Coin {
-                        denom: offer_denom,
-                        amount: offer_amount,
-                    },
-                    ask_denom,
-                )?;
-
-                offer_amount = res.receive.amount;
-            }
-            SwapOperation::TerraSwap {
-                offer_asset_info,
-                ask_asset_info,
-            } => {
-                let pair_info: PairInfo = query_pair_info(
-                    &deps.querier,
-                    terraswap_factory.clone(),
-                    &[offer_asset_info.clone(), ask_asset_info.clone()],
-                )?;
-
-                // Deduct tax before querying simulation
-                if let AssetInfo::NativeToken { denom } = offer_asset_info.clone() {
-                    offer_amount = offer_amount.checked_sub(compute_tax(
-                        &deps.querier,
-                        offer_amount,
-                        denom,
-                    )?)?;
-                }
-
-                let mut res: SimulationResponse =
-                    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-                        contract_addr: pair_info.contract_addr.to_string(),
-                        msg: to_binary(&PairQueryMsg::Simulation {
-                            offer_asset: Asset {
-                                info: offer_asset_info,
-                                amount: offer_amount,
-                            },
-                        })?,
-                    }))?;
-
-                // Deduct tax after querying simulation
-                if let AssetInfo::NativeToken { denom } = ask_asset_info {
-                    res.return_amount = res.return_amount.checked_sub(compute_tax(
-                        &deps.querier,
-                        res.return_amount,
-                        denom,
-                    )?)?;
-                }
-
-                offer_amount = res.return_amount;
-            }
-            SwapOperation::WormHoleBridge { .. } => {} // todo
-            SwapOperation::IbcTransfer { .. } => {} // todo
-        }
-    }
-
-    Ok(SimulateSwapOperationsResponse {
-        amount: offer_amount,
-    })
-}
-
-fn assert_operations(operations: &[SwapOperation]) -> StdResult<()> {
-    let mut ask_asset_map: HashMap<String, bool> = HashMap::new();
-    for operation in operations.iter() {
-        let (offer_asset, ask_asset) = match operation {
-            SwapOperation::NativeSwap {
-                offer_denom,
-                ask_denom,
-            } => (
-                AssetInfo::NativeToken {
-                    denom: offer_denom.clone(),
-                },
-                AssetInfo::NativeToken {
-                    denom: ask_denom.clone(),
-                },
-            ),
-            SwapOperation::TerraSwap {
-                offer_asset_info,
-                ask_asset_info,
-            } => (offer_asset_info.clone(), ask_asset_info.clone()),
-            SwapOperation::WormHoleBridge { .. } => return Err(StdError::generic_err("not implemented")),// todo
-            SwapOperation::IbcTransfer { .. } => return Err(StdError::generic_err("not implemented"))// todo
-        };
-
-        ask_asset_map.remove(&offer_asset.to_string());
-        ask_asset_map.insert(ask_asset.to_string(), true);
-    }
-
-    if ask_asset_map.keys().len() != 1 {
-        return Err(StdError::generic_err(
-            "invalid operations; multiple output token",
-        ));
-    }
-
-    Ok(())
-}
-
-#[test]
-fn test_invalid_operations() {
-    // empty error
-    assert!(assert_operations(&[]).is_err());
-
-    // uluna output
-    assert!(assert_operations(&vec![
-        SwapOperation::NativeSwap {
-            offer_denom: "uusd".to_string(),
-            ask_denom: "uluna".to_string(),
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-        },
-    ])
-        .is_ok());
-
-    // asset0002 output
-    assert!(assert_operations(&vec![
-        SwapOperation::NativeSwap {
-            offer_denom: "uusd".to_string(),
-            ask_denom: "uluna".to_string(),
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0002".to_string(),
-            },
-        },
-    ])
-        .is_ok());
-
-    // multiple output token types error
-    assert!(assert_operations(&vec![
-        SwapOperation::NativeSwap {
-            offer_denom: "uusd".to_string(),
-            ask_denom: "ukrw".to_string(),
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
-                denom: "uaud".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0002".to_string(),
-            },
-        },
-    ])
-        .is_err());
-}
@@ -1,8 +1,5 @@
 pub mod contract;
 pub mod state;
 
-mod operations;
-mod querier;
-
 #[cfg(test)]
 mod testing;
@@ -1,19 +1,10 @@
-use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{
-    from_binary, to_binary, Coin, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
-};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_binary, Coin, StdError, Uint128};
 
 use crate::contract::{execute, instantiate, query};
-use crate::testing::mock_querier::mock_dependencies;
 
-use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use terra_cosmwasm::{create_swap_msg, create_swap_send_msg};
-use terraswap::asset::{Asset, AssetInfo};
-use terraswap::pair::ExecuteMsg as PairExecuteMsg;
-use terraswap::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation,
-};
+use terraswap::asset::AssetInfo;
+use terraswap::router::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SwapOperation};
 
 #[test]
 fn proper_initialization() {
@@ -35,545 +26,62 @@ fn proper_initialization() {
 }
 
 #[test]
-fn execute_swap_operations() {
+fn execute_teleport_unsupported_operations() {
     let mut deps = mock_dependencies(&[]);
-    deps.querier.with_token_balances(&[(
-        &"asset0002".to_string(),
-        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
-    )]);
-
     let msg = InstantiateMsg {
         terraswap_factory: "terraswapfactory".to_string(),
     };
 
     let info = mock_info("addr0000", &[]);
-
-    // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    let msg = ExecuteMsg::ExecuteSwapOperations {
-        operations: vec![],
-        minimum_receive: None,
-        to: None,
+    let swap = SwapOperation::NativeSwap {
+        offer_denom: "uusd".to_string(),
+        ask_denom: "uluna".to_string(),
     };
-
-    let info = mock_info("addr0000", &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg);
-    match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "must provide operations"),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
-    let msg = ExecuteMsg::ExecuteSwapOperations {
-        operations: vec![
-            SwapOperation::NativeSwap {
-                offer_denom: "uusd".to_string(),
-                ask_denom: "ukrw".to_string(),
-            },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "ukrw".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0001".to_string(),
-                },
-            },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0001".to_string(),
-                },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
-                },
+    let unsupported_operations = vec![
+        SwapOperation::Pool {
+            pair_address: "pair0000".to_string(),
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
             },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0002".to_string(),
-                },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
             },
-        ],
-        minimum_receive: Some(Uint128::from(1000000u128)),
-        to: None,
-    };
-
-    let info = mock_info("addr0000", &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_eq!(
-        res.messages,
-        vec![
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::NativeSwap {
-                        offer_denom: "uusd".to_string(),
-                        ask_denom: "ukrw".to_string(),
-                    },
-                    to: None,
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::TerraSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: "ukrw".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0001".to_string(),
-                        },
-                    },
-                    to: None,
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::TerraSwap {
-                        offer_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0001".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                    },
-                    to: None,
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::TerraSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0002".to_string(),
-                        },
-                    },
-                    to: Some("addr0000".to_string()),
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
-                    asset_info: AssetInfo::Token {
-                        contract_addr: "asset0002".to_string(),
-                    },
-                    prev_balance: Uint128::zero(),
-                    minimum_receive: Uint128::from(1000000u128),
-                    receiver: "addr0000".to_string(),
-                })
-                .unwrap(),
-            })),
-        ]
-    );
-
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: "addr0000".to_string(),
-        amount: Uint128::from(1000000u128),
-        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
-            operations: vec![
-                SwapOperation::NativeSwap {
-                    offer_denom: "uusd".to_string(),
-                    ask_denom: "ukrw".to_string(),
-                },
-                SwapOperation::TerraSwap {
-                    offer_asset_info: AssetInfo::NativeToken {
-                        denom: "ukrw".to_string(),
-                    },
-                    ask_asset_info: AssetInfo::Token {
-                        contract_addr: "asset0001".to_string(),
-                    },
-                },
-                SwapOperation::TerraSwap {
-                    offer_asset_info: AssetInfo::Token {
-                        contract_addr: "asset0001".to_string(),
-                    },
-                    ask_asset_info: AssetInfo::NativeToken {
-                        denom: "uluna".to_string(),
-                    },
-                },
-                SwapOperation::TerraSwap {
-                    offer_asset_info: AssetInfo::NativeToken {
-                        denom: "uluna".to_string(),
-                    },
-                    ask_asset_info: AssetInfo::Token {
-                        contract_addr: "asset0002".to_string(),
-                    },
-                },
-            ],
-            minimum_receive: None,
-            to: Some("addr0002".to_string()),
-        })
-        .unwrap(),
-    });
-
-    let info = mock_info("asset0000", &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_eq!(
-        res.messages,
-        vec![
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::NativeSwap {
-                        offer_denom: "uusd".to_string(),
-                        ask_denom: "ukrw".to_string(),
-                    },
-                    to: None,
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::TerraSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: "ukrw".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0001".to_string(),
-                        },
-                    },
-                    to: None,
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::TerraSwap {
-                        offer_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0001".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                    },
-                    to: None,
-                })
-                .unwrap(),
-            })),
-            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: SwapOperation::TerraSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: "asset0002".to_string(),
-                        },
-                    },
-                    to: Some("addr0002".to_string()),
-                })
-                .unwrap(),
-            }))
-        ]
-    );
-}
-
-#[test]
-fn execute_swap_operation() {
-    let mut deps = mock_dependencies(&[]);
-    let msg = InstantiateMsg {
-        terraswap_factory: "terraswapfactory".to_string(),
-    };
-
-    let info = mock_info("addr0000", &[]);
-
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    deps.querier
-        .with_terraswap_pairs(&[(&"uusdasset".to_string(), &"pair".to_string())]);
-    deps.querier.with_tax(
-        Decimal::percent(5),
-        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
-    );
-    deps.querier.with_balance(&[(
-        MOCK_CONTRACT_ADDR.to_string(),
-        &[Coin {
-            amount: Uint128::from(1000000u128),
-            denom: "uusd".to_string(),
-        }],
-    )]);
-
-    let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::NativeSwap {
-            offer_denom: "uusd".to_string(),
-            ask_denom: "uluna".to_string(),
+            protocol: "terraswap".to_string(),
+            max_spread: None,
+            belief_price: None,
         },
-        to: None,
-    };
-    let info = mock_info("addr0000", &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
-    match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(create_swap_msg(
-            Coin {
-                denom: "uusd".to_string(),
-                amount: Uint128::from(1000000u128),
+        SwapOperation::WormHoleBridge {
+            asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
             },
-            "uluna".to_string()
-        ))],
-    );
-
-    // optional to address
-    // swap_send
-    let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::NativeSwap {
-            offer_denom: "uusd".to_string(),
-            ask_denom: "uluna".to_string(),
+            wallet_address_on_target_chain: "wallet0000".to_string(),
         },
-        to: Some("addr0000".to_string()),
-    };
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(create_swap_send_msg(
-            "addr0000".to_string(),
-            Coin {
-                denom: "uusd".to_string(),
-                amount: Uint128::from(952380u128), // deduct tax
-            },
-            "uluna".to_string()
-        ))],
-    );
-    deps.querier
-        .with_terraswap_pairs(&[(&"assetuusd".to_string(), &"pair".to_string())]);
-    deps.querier.with_token_balances(&[(
-        &"asset".to_string(),
-        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
-    )]);
+    ];
 
-    let msg = ExecuteMsg::ExecuteSwapOperation {
-        operation: SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
+    // the teleport router must not skip an operation it cannot run
+    for operation in unsupported_operations {
+        let msg = ExecuteMsg::ExecuteTeleport {
+            operations: vec![swap.clone(), operation],
+            minimum_receive: None,
+            ref_address: None,
+            ref_fee_pct: None,
+            to: None,
+        };
+        let info = mock_info(
+            "addr0000",
+            &[Coin {
                 denom: "uusd".to_string(),
-            },
-        },
-        to: Some("addr0000".to_string()),
-    };
-
-    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "asset".to_string(),
-            funds: vec![],
-            msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: "pair".to_string(),
                 amount: Uint128::from(1000000u128),
-                msg: to_binary(&PairExecuteMsg::Swap {
-                    offer_asset: Asset {
-                        info: AssetInfo::Token {
-                            contract_addr: "asset".to_string(),
-                        },
-                        amount: Uint128::from(1000000u128),
-                    },
-                    belief_price: None,
-                    max_spread: None,
-                    to: Some("addr0000".to_string()),
-                })
-                .unwrap()
-            })
-            .unwrap()
-        }))]
-    );
-}
-
-#[test]
-fn query_buy_with_routes() {
-    let mut deps = mock_dependencies(&[]);
-
-    let msg = InstantiateMsg {
-        terraswap_factory: "terraswapfactory".to_string(),
-    };
-
-    let info = mock_info("addr0000", &[]);
-
-    // we can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    // set tax rate as 5%
-    deps.querier.with_tax(
-        Decimal::percent(5),
-        &[
-            (&"uusd".to_string(), &Uint128::from(1000000u128)),
-            (&"ukrw".to_string(), &Uint128::from(1000000u128)),
-        ],
-    );
-
-    let msg = QueryMsg::SimulateSwapOperations {
-        offer_amount: Uint128::from(1000000u128),
-        operations: vec![
-            SwapOperation::NativeSwap {
-                offer_denom: "uusd".to_string(),
-                ask_denom: "ukrw".to_string(),
-            },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::NativeToken {
-                    denom: "ukrw".to_string(),
-                },
-                ask_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-            },
-            SwapOperation::TerraSwap {
-                offer_asset_info: AssetInfo::Token {
-                    contract_addr: "asset0000".to_string(),
-                },
-                ask_asset_info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string(),
-                },
-            },
-        ],
-    };
-
-    deps.querier.with_terraswap_pairs(&[
-        (&"ukrwasset0000".to_string(), &"pair0000".to_string()),
-        (&"asset0000uluna".to_string(), &"pair0001".to_string()),
-    ]);
-
-    let res: SimulateSwapOperationsResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
-    assert_eq!(
-        res,
-        SimulateSwapOperationsResponse {
-            amount: Uint128::from(952380u128), // tax charged 1 times uusd => ukrw, ukrw => asset0000, asset0000 => uluna
-        }
-    );
-
-    let msg = QueryMsg::SimulateSwapOperations {
-        offer_amount: Uint128::from(1000000u128),
-        operations: vec![
-            SwapOperation::NativeSwap {
-                offer_denom: "uusd".to_string(),
-                ask_denom: "ukrw".to_string(),
-            },
-            SwapOperation::NativeSwap {
-                offer_denom: "ukrw".to_string(),
-                ask_denom: "uluna".to_string(),
-            },
-        ],
-    };
-
-    let res: SimulateSwapOperationsResponse =
-        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
-    assert_eq!(
-        res,
-        SimulateSwapOperationsResponse {
-            amount: Uint128::from(952380u128), // tax charged 1 times uusd => ukrw, ukrw => uluna
+            }],
+        );
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                msg,
+                "invalid operations; operation 1 is not supported by the teleport router"
+            ),
+            _ => panic!("DO NOT ENTER HERE"),
         }
-    );
-}
-
-#[test]
-fn assert_minimum_receive_native_token() {
-    let mut deps = mock_dependencies(&[]);
-    deps.querier.with_balance(&[(
-        "addr0000".to_string(),
-        &[Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(1000000u128),
-        }],
-    )]);
-
-    let info = mock_info("addr0000", &[]);
-    // success
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::NativeToken {
-            denom: "uusd".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000000u128),
-        receiver: "addr0000".to_string(),
-    };
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-
-    // assertion failed; native token
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::NativeToken {
-            denom: "uusd".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000001u128),
-        receiver: "addr0000".to_string(),
-    };
-    let res = execute(deps.as_mut(), mock_env(), info, msg);
-    match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
-            msg,
-            "assertion failed; minimum receive amount: 1000001, swap amount: 1000000"
-        ),
-        _ => panic!("DO NOT ENTER HERE"),
-    }
-}
-
-#[test]
-fn assert_minimum_receive_token() {
-    let mut deps = mock_dependencies(&[]);
-    deps.querier.with_token_balances(&[(
-        &"token0000".to_string(),
-        &[(&"addr0000".to_string(), &Uint128::from(1000000u128))],
-    )]);
-
-    let info = mock_info("addr0000", &[]);
-    // success
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::Token {
-            contract_addr: "token0000".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000000u128),
-        receiver: "addr0000".to_string(),
-    };
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-
-    // assertion failed; native token
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::Token {
-            contract_addr: "token0000".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000001u128),
-        receiver: "addr0000".to_string(),
-    };
-    let res = execute(deps.as_mut(), mock_env(), info, msg);
-    match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
-            msg,
-            "assertion failed; minimum receive amount: 1000001, swap amount: 1000000"
-        ),
-        _ => panic!("DO NOT ENTER HERE"),
     }
 }
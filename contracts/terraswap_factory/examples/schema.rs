@@ -4,7 +4,11 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use terraswap::asset::PairInfo;
-use terraswap::factory::{ConfigResponse, ExecuteMsg, InstantiateMsg, PairsResponse, QueryMsg};
+use terraswap::factory::{
+    AllowedCreatorsResponse, ApprovedTokensResponse, ConfigResponse, ExecuteMsg, InstantiateMsg,
+    NativeTokenDecimalsResponse, PairCountResponse, PairsByCreationResponse, PairsResponse,
+    QueryMsg, RetiredPairResponse, RetiredPairsResponse,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -17,5 +21,12 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(PairInfo), &out_dir);
     export_schema(&schema_for!(PairsResponse), &out_dir);
+    export_schema(&schema_for!(PairCountResponse), &out_dir);
+    export_schema(&schema_for!(PairsByCreationResponse), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(AllowedCreatorsResponse), &out_dir);
+    export_schema(&schema_for!(ApprovedTokensResponse), &out_dir);
+    export_schema(&schema_for!(RetiredPairResponse), &out_dir);
+    export_schema(&schema_for!(RetiredPairsResponse), &out_dir);
+    export_schema(&schema_for!(NativeTokenDecimalsResponse), &out_dir);
 }
@@ -1,20 +1,39 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response, StdError,
-    StdResult, SubMsg, WasmMsg,
+    to_binary, Addr, Binary, Coin, ContractResult, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, WasmMsg,
 };
 
+use crate::error::ContractError;
 use crate::querier::query_liquidity_token;
 use crate::response::MsgInstantiateContractResponse;
-use crate::state::{pair_key, read_pairs, Config, TmpPairInfo, CONFIG, PAIRS, TMP_PAIR_INFO};
+use crate::state::{
+    add_pair, append_pair_creation, next_reply_id, pair_key, pairs, read_allowed_creators,
+    read_approved_tokens, read_pairs, read_pairs_by_asset, read_pairs_by_creation,
+    read_retired_pairs, read_stored_pairs, remove_pair, Config, RetiredPair, TmpPairInfo,
+    TmpProvideInfo, ALLOWED_CREATORS, APPROVED_TOKENS, CONFIG, NATIVE_TOKEN_DECIMALS,
+    OWNERSHIP_PROPOSAL, PAIR_COUNT, PAIR_CREATION_INDEX, PENDING_PAIRS, RETIRED_PAIRS,
+};
 
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::U64Key;
 use protobuf::Message;
-use terraswap::asset::{AssetInfo, PairInfo, PairInfoRaw};
+use terraswap::asset::{Asset, AssetInfo, PairInfo, PairInfoRaw};
 use terraswap::factory::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PairsResponse, QueryMsg,
+    AllowedCreatorsResponse, ApprovedTokensResponse, ConfigResponse, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, NativeTokenDecimalsResponse, PairByCreationResponse, PairCountResponse,
+    PairCreationMode, PairsByCreationResponse, PairsResponse, QueryMsg, RetiredPairResponse,
+    RetiredPairsResponse,
+};
+use terraswap::ownership::{
+    assert_owner, claim_ownership, drop_ownership_proposal, propose_new_owner,
+    query_ownership_proposal, renounce_ownership,
+};
+use terraswap::pair::{
+    ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg, PairType, PoolResponse,
 };
-use terraswap::pair::InstantiateMsg as PairInstantiateMsg;
+use terraswap::querier::{query_pool, query_token_info};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -24,9 +43,10 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     let config = Config {
-        owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+        owner: Some(deps.api.addr_canonicalize(info.sender.as_str())?),
         token_code_id: msg.token_code_id,
         pair_code_id: msg.pair_code_id,
+        pair_creation_mode: PairCreationMode::Permissionless {},
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -35,14 +55,86 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::UpdateConfig {
-            owner,
             token_code_id,
             pair_code_id,
-        } => execute_update_config(deps, env, info, owner, token_code_id, pair_code_id),
-        ExecuteMsg::CreatePair { asset_infos } => execute_create_pair(deps, env, info, asset_infos),
+            pair_creation_mode,
+        } => execute_update_config(
+            deps,
+            env,
+            info,
+            token_code_id,
+            pair_code_id,
+            pair_creation_mode,
+        ),
+        ExecuteMsg::DeregisterPair { asset_infos } => {
+            execute_deregister_pair(deps, env, info, asset_infos)
+        }
+        ExecuteMsg::RegisterPair { pair_info } => execute_register_pair(deps, env, info, pair_info),
+        ExecuteMsg::UpdateAllowedCreators { add, remove } => {
+            execute_update_allowed_creators(deps, env, info, add, remove)
+        }
+        ExecuteMsg::UpdateApprovedTokens { add, remove } => {
+            execute_update_approved_tokens(deps, env, info, add, remove)
+        }
+        ExecuteMsg::AddNativeTokenDecimals { denom, decimals } => {
+            execute_add_native_token_decimals(deps, env, info, denom, decimals)
+        }
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => Ok(propose_new_owner(
+            deps,
+            env,
+            info,
+            &CONFIG,
+            &OWNERSHIP_PROPOSAL,
+            owner,
+            expires_in,
+        )?),
+        ExecuteMsg::ClaimOwnership {} => Ok(claim_ownership(
+            deps,
+            env,
+            info,
+            &CONFIG,
+            &OWNERSHIP_PROPOSAL,
+        )?),
+        ExecuteMsg::DropOwnershipProposal {} => Ok(drop_ownership_proposal(
+            deps,
+            info,
+            &CONFIG,
+            &OWNERSHIP_PROPOSAL,
+        )?),
+        ExecuteMsg::RenounceOwnership {} => Ok(renounce_ownership(
+            deps,
+            info,
+            &CONFIG,
+            &OWNERSHIP_PROPOSAL,
+        )?),
+        ExecuteMsg::CreatePair {
+            asset_infos,
+            pair_type,
+        } => execute_create_pair(deps, env, info, asset_infos, pair_type),
+        ExecuteMsg::CreatePairAndProvide {
+            asset_infos,
+            pair_type,
+            assets,
+            slippage_tolerance,
+            receiver,
+        } => execute_create_pair_and_provide(
+            deps,
+            env,
+            info,
+            asset_infos,
+            pair_type,
+            assets,
+            slippage_tolerance,
+            receiver,
+        ),
     }
 }
 
@@ -51,22 +143,26 @@ pub fn execute_update_config(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    owner: Option<String>,
     token_code_id: Option<u64>,
     pair_code_id: Option<u64>,
-) -> StdResult<Response> {
+    pair_creation_mode: Option<PairCreationMode>,
+) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
     // permission check
-    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
-        return Err(StdError::generic_err("unauthorized"));
-    }
+    assert_owner(deps.as_ref(), &config, &info)?;
 
-    if let Some(owner) = owner {
-        // validate address format
-        let _ = deps.api.addr_validate(&owner)?;
+    if let Some(pair_creation_mode) = pair_creation_mode {
+        if let PairCreationMode::Fee { fee, fee_collector } = &pair_creation_mode {
+            if !fee.is_native_token() || fee.amount.is_zero() {
+                return Err(ContractError::InvalidCreationFee {});
+            }
 
-        config.owner = deps.api.addr_canonicalize(&owner)?;
+            // validate address format
+            deps.api.addr_validate(fee_collector)?;
+        }
+
+        config.pair_creation_mode = pair_creation_mode;
     }
 
     if let Some(token_code_id) = token_code_id {
@@ -82,39 +178,327 @@ pub fn execute_update_config(
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
-// Anyone can execute it to create swap pair
+// Only owner can execute it
+pub fn execute_deregister_pair(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    assert_owner(deps.as_ref(), &config, &info)?;
+
+    let pair_key = pair_key(&[
+        asset_infos[0].to_raw(deps.api)?,
+        asset_infos[1].to_raw(deps.api)?,
+    ]);
+    let pair_info: PairInfoRaw = pairs().load(deps.storage, &pair_key)?;
+
+    remove_pair(deps.storage, &pair_key, &pair_info)?;
+    RETIRED_PAIRS.save(
+        deps.storage,
+        pair_info.contract_addr.as_slice(),
+        &RetiredPair {
+            pair_info: pair_info.clone(),
+            retired_at: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "deregister_pair"),
+        ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
+        (
+            "pair_contract_addr",
+            deps.api.addr_humanize(&pair_info.contract_addr)?.as_str(),
+        ),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_register_pair(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    pair_info: PairInfo,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    assert_owner(deps.as_ref(), &config, &info)?;
+
+    let raw_infos = [
+        pair_info.asset_infos[0].to_raw(deps.api)?,
+        pair_info.asset_infos[1].to_raw(deps.api)?,
+    ];
+
+    let pair_key = pair_key(&raw_infos);
+    if let Ok(Some(_)) = pairs().may_load(deps.storage, &pair_key) {
+        return Err(ContractError::PairExists {});
+    }
+
+    // validate address format
+    let contract_addr = deps.api.addr_validate(&pair_info.contract_addr)?;
+    let liquidity_token = deps.api.addr_validate(&pair_info.liquidity_token)?;
+    let contract_addr_raw = deps.api.addr_canonicalize(contract_addr.as_str())?;
+
+    add_pair(
+        deps.storage,
+        &pair_key,
+        &PairInfoRaw {
+            contract_addr: contract_addr_raw.clone(),
+            liquidity_token: deps.api.addr_canonicalize(liquidity_token.as_str())?,
+            asset_infos: raw_infos,
+            asset_decimals: pair_info.asset_decimals,
+        },
+    )?;
+
+    // a retired pair registered again is no longer retired
+    RETIRED_PAIRS.remove(deps.storage, contract_addr_raw.as_slice());
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "register_pair"),
+        (
+            "pair",
+            &format!("{}-{}", pair_info.asset_infos[0], pair_info.asset_infos[1]),
+        ),
+        ("pair_contract_addr", contract_addr.as_str()),
+        ("liquidity_token_addr", liquidity_token.as_str()),
+    ]))
+}
+
+// Only owner can execute it
+pub fn execute_update_allowed_creators(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    assert_owner(deps.as_ref(), &config, &info)?;
+
+    for creator in add.iter() {
+        let creator = deps
+            .api
+            .addr_canonicalize(deps.api.addr_validate(creator)?.as_str())?;
+        ALLOWED_CREATORS.save(deps.storage, creator.as_slice(), &true)?;
+    }
+
+    for creator in remove.iter() {
+        let creator = deps
+            .api
+            .addr_canonicalize(deps.api.addr_validate(creator)?.as_str())?;
+        ALLOWED_CREATORS.remove(deps.storage, creator.as_slice());
+    }
+
+    Ok(Response::new().add_attribute("action", "update_allowed_creators"))
+}
+
+// Only owner can execute it
+pub fn execute_update_approved_tokens(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<AssetInfo>,
+    remove: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    assert_owner(deps.as_ref(), &config, &info)?;
+
+    for asset_info in add.iter() {
+        let raw_info = asset_info.to_raw(deps.api)?;
+        APPROVED_TOKENS.save(deps.storage, raw_info.as_bytes(), &raw_info)?;
+    }
+
+    for asset_info in remove.iter() {
+        let raw_info = asset_info.to_raw(deps.api)?;
+        APPROVED_TOKENS.remove(deps.storage, raw_info.as_bytes());
+    }
+
+    Ok(Response::new().add_attribute("action", "update_approved_tokens"))
+}
+
+// Only owner can execute it
+pub fn execute_add_native_token_decimals(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    denom: String,
+    decimals: u8,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    assert_owner(deps.as_ref(), &config, &info)?;
+
+    // only an existing denom can be deposited
+    if !info
+        .funds
+        .iter()
+        .any(|coin| coin.denom == denom && !coin.amount.is_zero())
+    {
+        return Err(ContractError::NativeTokenDepositRequired { denom });
+    }
+
+    NATIVE_TOKEN_DECIMALS.save(deps.storage, denom.as_bytes(), &decimals)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_native_token_decimals"),
+        ("denom", &denom),
+        ("decimals", &decimals.to_string()),
+    ]))
+}
+
+// Anyone allowed by the pair creation mode can execute it to create swap pair
 pub fn execute_create_pair(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     asset_infos: [AssetInfo; 2],
-) -> StdResult<Response> {
+    pair_type: Option<PairType>,
+) -> Result<Response, ContractError> {
+    create_pair(deps, &info, asset_infos, pair_type, None)
+}
+
+// Anyone allowed by the pair creation mode can execute it to create swap pair
+// and provide the initial liquidity, which is forwarded to the pair after its instantiation
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_pair_and_provide(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    pair_type: Option<PairType>,
+    assets: [Asset; 2],
+    slippage_tolerance: Option<Decimal>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    for asset in assets.iter() {
+        if !asset_infos.iter().any(|info| info.equal(&asset.info)) {
+            return Err(ContractError::AssetNotInPair {
+                asset: asset.info.to_string(),
+            });
+        }
+    }
+
+    if assets[0].info.equal(&assets[1].info) || assets.iter().any(|a| a.amount.is_zero()) {
+        return Err(ContractError::InvalidInitialLiquidity {});
+    }
+
+    // the provided native tokens are sent along with the creation fee
+    let mut funds = info.funds.clone();
+    for asset in assets.iter() {
+        if let AssetInfo::NativeToken { denom } = &asset.info {
+            let coin = funds
+                .iter_mut()
+                .find(|coin| coin.denom == *denom && coin.amount >= asset.amount)
+                .ok_or_else(|| {
+                    StdError::generic_err(
+                        "Native token balance mismatch between the argument and the transferred",
+                    )
+                })?;
+            coin.amount = coin.amount.checked_sub(asset.amount)?;
+        }
+    }
+    funds.retain(|coin| !coin.amount.is_zero());
+
+    let receiver = match receiver {
+        Some(receiver) => deps.api.addr_validate(&receiver)?,
+        None => info.sender.clone(),
+    };
+
+    let provide_info = TmpProvideInfo {
+        assets: [assets[0].to_raw(deps.api)?, assets[1].to_raw(deps.api)?],
+        slippage_tolerance,
+        receiver: deps.api.addr_canonicalize(receiver.as_str())?,
+    };
+
+    let fee_info = MessageInfo {
+        sender: info.sender.clone(),
+        funds,
+    };
+    let res = create_pair(deps, &fee_info, asset_infos, pair_type, Some(provide_info))?;
+
+    // pull the cw20 tokens before the pair is instantiated
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for asset in assets.iter() {
+        if let AssetInfo::Token { contract_addr } = &asset.info {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: asset.amount,
+                })?,
+                funds: vec![],
+            }));
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(res.messages)
+        .add_attributes(res.attributes)
+        .add_attribute("receiver", receiver.as_str()))
+}
+
+/// Stores the pending creation under a fresh reply id and instantiates the pair
+fn create_pair(
+    deps: DepsMut,
+    info: &MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    pair_type: Option<PairType>,
+    provide_info: Option<TmpProvideInfo>,
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
     let raw_infos = [
         asset_infos[0].to_raw(deps.api)?,
         asset_infos[1].to_raw(deps.api)?,
     ];
 
+    // pairs of another type trust the prices they are given, so only the owner creates them
+    if !matches!(pair_type, None | Some(PairType::ConstantProduct {})) {
+        assert_owner(deps.as_ref(), &config, info)?;
+    }
+
+    let fee_messages = assert_pair_creation_allowed(deps.as_ref(), &config, info, &asset_infos)?;
+
     let pair_key = pair_key(&raw_infos);
-    if let Ok(Some(_)) = PAIRS.may_load(deps.storage, &pair_key) {
-        return Err(StdError::generic_err("Pair already exists"));
+    if let Ok(Some(_)) = pairs().may_load(deps.storage, &pair_key) {
+        return Err(ContractError::PairExists {});
     }
 
-    TMP_PAIR_INFO.save(
+    let asset_decimals = [
+        query_decimals(deps.as_ref(), &asset_infos[0])?,
+        query_decimals(deps.as_ref(), &asset_infos[1])?,
+    ];
+
+    let reply_id = next_reply_id(deps.storage)?;
+    PENDING_PAIRS.save(
         deps.storage,
+        U64Key::new(reply_id),
         &TmpPairInfo {
             pair_key,
             asset_infos: raw_infos,
+            asset_decimals,
+            provide_info,
         },
     )?;
 
     Ok(Response::new()
+        .add_messages(fee_messages)
         .add_attributes(vec![
             ("action", "create_pair"),
             ("pair", &format!("{}-{}", asset_infos[0], asset_infos[1])),
         ])
         .add_submessage(SubMsg {
-            id: 1,
+            id: reply_id,
             gas_limit: None,
             msg: WasmMsg::Instantiate {
                 code_id: config.pair_code_id,
@@ -123,60 +507,258 @@ pub fn execute_create_pair(
                 label: "".to_string(),
                 msg: to_binary(&PairInstantiateMsg {
                     asset_infos,
+                    asset_decimals,
                     token_code_id: config.token_code_id,
+                    pair_type,
                 })?,
             }
             .into(),
-            reply_on: ReplyOn::Success,
+            reply_on: ReplyOn::Always,
         }))
 }
 
-/// This just stores the result for future query
+/// Decimals of the asset, cw20 tokens are queried and native tokens must be registered
+fn query_decimals(deps: Deps, asset_info: &AssetInfo) -> Result<u8, ContractError> {
+    match asset_info {
+        AssetInfo::Token { contract_addr } => {
+            let token_info =
+                query_token_info(&deps.querier, deps.api.addr_validate(contract_addr)?)?;
+            Ok(token_info.decimals)
+        }
+        AssetInfo::NativeToken { denom } => NATIVE_TOKEN_DECIMALS
+            .may_load(deps.storage, denom.as_bytes())?
+            .ok_or_else(|| ContractError::NativeTokenDecimalsNotFound {
+                denom: denom.to_string(),
+            }),
+    }
+}
+
+/// Checks the sender against the pair creation mode,
+/// returns the messages forwarding the creation fee to the fee collector
+fn assert_pair_creation_allowed(
+    deps: Deps,
+    config: &Config,
+    info: &MessageInfo,
+    asset_infos: &[AssetInfo; 2],
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    // funds other than the creation fee would be stuck in the factory
+    let fee_denom = match &config.pair_creation_mode {
+        PairCreationMode::Fee {
+            fee:
+                Asset {
+                    info: AssetInfo::NativeToken { denom },
+                    ..
+                },
+            ..
+        } => Some(denom),
+        _ => None,
+    };
+    if let Some(coin) = info
+        .funds
+        .iter()
+        .find(|coin| Some(&coin.denom) != fee_denom && !coin.amount.is_zero())
+    {
+        return Err(ContractError::UnexpectedFunds {
+            denom: coin.denom.to_string(),
+        });
+    }
+
+    match &config.pair_creation_mode {
+        PairCreationMode::Permissionless {} => Ok(vec![]),
+        PairCreationMode::Allowlist {} => {
+            let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+            if config.owner.as_ref() != Some(&sender)
+                && !ALLOWED_CREATORS.has(deps.storage, sender.as_slice())
+            {
+                return Err(ContractError::CreatorNotAllowed {});
+            }
+
+            Ok(vec![])
+        }
+        PairCreationMode::ApprovedTokens {} => {
+            for asset_info in asset_infos.iter() {
+                let raw_info = asset_info.to_raw(deps.api)?;
+                if !APPROVED_TOKENS.has(deps.storage, raw_info.as_bytes()) {
+                    return Err(ContractError::AssetNotApproved {
+                        asset: asset_info.to_string(),
+                    });
+                }
+            }
+
+            Ok(vec![])
+        }
+        PairCreationMode::Fee { fee, fee_collector } => {
+            fee.assert_sent_native_token_balance(info)?;
+
+            Ok(vec![fee.clone().into_msg(
+                &deps.querier,
+                deps.api.addr_validate(fee_collector)?,
+            )?])
+        }
+    }
+}
+
+/// Stores the pair of the pending creation for future query
+/// and provides its initial liquidity if any
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
-    let tmp_pair_info = TMP_PAIR_INFO.load(deps.storage)?;
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let key = U64Key::new(msg.id);
+    let tmp_pair_info = PENDING_PAIRS
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::UnknownReplyId { id: msg.id })?;
+    PENDING_PAIRS.remove(deps.storage, key);
+
+    let data = match msg.result {
+        ContractResult::Ok(res) => res.data,
+        ContractResult::Err(reason) => {
+            return Err(ContractError::PairInstantiationFailed { reason })
+        }
+    }
+    .ok_or_else(|| StdError::parse_err("MsgInstantiateContractResponse", "missing data"))?;
 
     let res: MsgInstantiateContractResponse =
-        Message::parse_from_bytes(msg.result.unwrap().data.unwrap().as_slice()).map_err(|_| {
+        Message::parse_from_bytes(data.as_slice()).map_err(|_| {
             StdError::parse_err("MsgInstantiateContractResponse", "failed to parse data")
         })?;
 
+    // another creation of the same pair may have completed in this transaction
+    if pairs()
+        .may_load(deps.storage, &tmp_pair_info.pair_key)?
+        .is_some()
+    {
+        return Err(ContractError::PairExists {});
+    }
+
     let pair_contract = res.get_contract_address();
     let liquidity_token = query_liquidity_token(deps.as_ref(), Addr::unchecked(pair_contract))?;
 
-    PAIRS.save(
+    add_pair(
         deps.storage,
         &tmp_pair_info.pair_key,
         &PairInfoRaw {
             liquidity_token: deps.api.addr_canonicalize(liquidity_token.as_str())?,
             contract_addr: deps.api.addr_canonicalize(pair_contract)?,
             asset_infos: tmp_pair_info.asset_infos,
+            asset_decimals: tmp_pair_info.asset_decimals,
         },
     )?;
 
-    Ok(Response::new().add_attributes(vec![
+    let messages = match tmp_pair_info.provide_info {
+        Some(provide_info) => {
+            provide_initial_liquidity(deps.as_ref(), pair_contract, provide_info)?
+        }
+        None => vec![],
+    };
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
         ("pair_contract_addr", pair_contract),
         ("liquidity_token_addr", liquidity_token.as_str()),
     ]))
 }
 
+/// Approves the cw20 tokens to the new pair and provides them with the native tokens,
+/// which are sent after the tax
+fn provide_initial_liquidity(
+    deps: Deps,
+    pair_contract: &str,
+    provide_info: TmpProvideInfo,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut funds: Vec<Coin> = vec![];
+    let mut assets: Vec<Asset> = vec![];
+    for asset in provide_info.assets.iter() {
+        let mut asset = asset.to_normal(deps.api)?;
+        match &asset.info {
+            AssetInfo::Token { contract_addr } => {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: pair_contract.to_string(),
+                        amount: asset.amount,
+                        expires: None,
+                    })?,
+                    funds: vec![],
+                }));
+            }
+            AssetInfo::NativeToken { .. } => {
+                let coin = asset.deduct_tax(&deps.querier)?;
+                asset.amount = coin.amount;
+                funds.push(coin);
+            }
+        }
+
+        assets.push(asset);
+    }
+
+    // coins must be sorted by denom
+    funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: pair_contract.to_string(),
+        msg: to_binary(&PairExecuteMsg::ProvideLiquidity {
+            assets: [assets[0].clone(), assets[1].clone()],
+            slippage_tolerance: provide_info.slippage_tolerance,
+            receiver: Some(deps.api.addr_humanize(&provide_info.receiver)?.to_string()),
+        })?,
+        funds,
+    }));
+
+    Ok(messages)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::Pair { asset_infos } => to_binary(&query_pair(deps, asset_infos)?),
-        QueryMsg::Pairs { start_after, limit } => {
-            to_binary(&query_pairs(deps, start_after, limit)?)
+        QueryMsg::Pairs {
+            start_after,
+            limit,
+            include_pools,
+        } => to_binary(&query_pairs(deps, start_after, limit, include_pools)?),
+        QueryMsg::PairCount {} => to_binary(&query_pair_count(deps)?),
+        QueryMsg::PairsByCreation {
+            start_after_index,
+            limit,
+        } => to_binary(&query_pairs_by_creation(deps, start_after_index, limit)?),
+        QueryMsg::PairByAddress { address } => to_binary(&query_pair_by_address(deps, address)?),
+        QueryMsg::PairByLiquidityToken { token } => {
+            to_binary(&query_pair_by_liquidity_token(deps, token)?)
+        }
+        QueryMsg::PairsByAsset {
+            asset_info,
+            start_after,
+            limit,
+        } => to_binary(&query_pairs_by_asset(deps, asset_info, start_after, limit)?),
+        QueryMsg::RetiredPair { address } => to_binary(&query_retired_pair(deps, address)?),
+        QueryMsg::RetiredPairs { start_after, limit } => {
+            to_binary(&query_retired_pairs(deps, start_after, limit)?)
+        }
+        QueryMsg::AllowedCreators { start_after, limit } => {
+            to_binary(&query_allowed_creators(deps, start_after, limit)?)
+        }
+        QueryMsg::ApprovedTokens { start_after, limit } => {
+            to_binary(&query_approved_tokens(deps, start_after, limit)?)
+        }
+        QueryMsg::NativeTokenDecimals { denom } => {
+            to_binary(&query_native_token_decimals(deps, denom)?)
         }
     }
 }
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state: Config = CONFIG.load(deps.storage)?;
+    let ownership_proposal = query_ownership_proposal(deps, &OWNERSHIP_PROPOSAL)?;
+
     let resp = ConfigResponse {
-        owner: deps.api.addr_humanize(&state.owner)?.to_string(),
+        owner: state
+            .owner
+            .map(|owner| deps.api.addr_humanize(&owner).map(|addr| addr.to_string()))
+            .transpose()?,
         token_code_id: state.token_code_id,
         pair_code_id: state.pair_code_id,
+        ownership_proposal,
+        pair_creation_mode: state.pair_creation_mode,
     };
 
     Ok(resp)
@@ -187,7 +769,7 @@ pub fn query_pair(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<PairInfo
         asset_infos[0].to_raw(deps.api)?,
         asset_infos[1].to_raw(deps.api)?,
     ]);
-    let pair_info: PairInfoRaw = PAIRS.load(deps.storage, &pair_key)?;
+    let pair_info: PairInfoRaw = pairs().load(deps.storage, &pair_key)?;
     pair_info.to_normal(deps.api)
 }
 
@@ -195,6 +777,7 @@ pub fn query_pairs(
     deps: Deps,
     start_after: Option<[AssetInfo; 2]>,
     limit: Option<u32>,
+    include_pools: Option<bool>,
 ) -> StdResult<PairsResponse> {
     let start_after = if let Some(start_after) = start_after {
         Some([
@@ -206,12 +789,235 @@ pub fn query_pairs(
     };
 
     let pairs: Vec<PairInfo> = read_pairs(deps.storage, deps.api, start_after, limit)?;
-    let resp = PairsResponse { pairs };
+    let pools = if include_pools.unwrap_or(false) {
+        Some(
+            pairs
+                .iter()
+                .map(|pair| query_pool(&deps.querier, deps.api.addr_validate(&pair.contract_addr)?))
+                .collect::<StdResult<Vec<PoolResponse>>>()?,
+        )
+    } else {
+        None
+    };
+
+    let resp = PairsResponse { pairs, pools };
 
     Ok(resp)
 }
 
+pub fn query_pair_count(deps: Deps) -> StdResult<PairCountResponse> {
+    let count = PAIR_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    Ok(PairCountResponse { count })
+}
+
+pub fn query_pairs_by_creation(
+    deps: Deps,
+    start_after_index: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PairsByCreationResponse> {
+    let pairs = read_pairs_by_creation(deps.storage, deps.api, start_after_index, limit)?
+        .into_iter()
+        .map(|(index, pair_info)| PairByCreationResponse { index, pair_info })
+        .collect();
+
+    Ok(PairsByCreationResponse { pairs })
+}
+
+pub fn query_pair_by_address(deps: Deps, address: String) -> StdResult<PairInfo> {
+    let address = deps.api.addr_canonicalize(&address)?;
+    match pairs().idx.address.item(deps.storage, address.to_vec())? {
+        Some((_, pair_info)) => pair_info.to_normal(deps.api),
+        None => Err(StdError::not_found("PairInfoRaw")),
+    }
+}
+
+pub fn query_pair_by_liquidity_token(deps: Deps, token: String) -> StdResult<PairInfo> {
+    let token = deps.api.addr_canonicalize(&token)?;
+    match pairs()
+        .idx
+        .liquidity_token
+        .item(deps.storage, token.to_vec())?
+    {
+        Some((_, pair_info)) => pair_info.to_normal(deps.api),
+        None => Err(StdError::not_found("PairInfoRaw")),
+    }
+}
+
+pub fn query_pairs_by_asset(
+    deps: Deps,
+    asset_info: AssetInfo,
+    start_after: Option<[AssetInfo; 2]>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    let start_after = if let Some(start_after) = start_after {
+        Some([
+            start_after[0].to_raw(deps.api)?,
+            start_after[1].to_raw(deps.api)?,
+        ])
+    } else {
+        None
+    };
+
+    let pairs: Vec<PairInfo> = read_pairs_by_asset(
+        deps.storage,
+        deps.api,
+        &asset_info.to_raw(deps.api)?,
+        start_after,
+        limit,
+    )?;
+
+    Ok(PairsResponse { pairs, pools: None })
+}
+
+pub fn query_retired_pair(deps: Deps, address: String) -> StdResult<RetiredPairResponse> {
+    let address = deps.api.addr_canonicalize(&address)?;
+    let retired_pair: RetiredPair = RETIRED_PAIRS.load(deps.storage, address.as_slice())?;
+
+    Ok(RetiredPairResponse {
+        pair_info: retired_pair.pair_info.to_normal(deps.api)?,
+        retired_at: retired_pair.retired_at,
+    })
+}
+
+pub fn query_retired_pairs(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RetiredPairsResponse> {
+    let start_after = match start_after {
+        Some(start_after) => Some(deps.api.addr_canonicalize(&start_after)?),
+        None => None,
+    };
+
+    let pairs = read_retired_pairs(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|retired_pair| {
+            Ok(RetiredPairResponse {
+                pair_info: retired_pair.pair_info.to_normal(deps.api)?,
+                retired_at: retired_pair.retired_at,
+            })
+        })
+        .collect::<StdResult<Vec<RetiredPairResponse>>>()?;
+
+    Ok(RetiredPairsResponse { pairs })
+}
+
+pub fn query_allowed_creators(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllowedCreatorsResponse> {
+    let start_after = match start_after {
+        Some(start_after) => Some(deps.api.addr_canonicalize(&start_after)?),
+        None => None,
+    };
+
+    let creators = read_allowed_creators(deps.storage, deps.api, start_after, limit)?;
+    Ok(AllowedCreatorsResponse { creators })
+}
+
+pub fn query_approved_tokens(
+    deps: Deps,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> StdResult<ApprovedTokensResponse> {
+    let start_after = match start_after {
+        Some(start_after) => Some(start_after.to_raw(deps.api)?),
+        None => None,
+    };
+
+    let asset_infos = read_approved_tokens(deps.storage, deps.api, start_after, limit)?;
+    Ok(ApprovedTokensResponse { asset_infos })
+}
+
+pub fn query_native_token_decimals(
+    deps: Deps,
+    denom: String,
+) -> StdResult<NativeTokenDecimalsResponse> {
+    let decimals = NATIVE_TOKEN_DECIMALS.load(deps.storage, denom.as_bytes())?;
+    Ok(NativeTokenDecimalsResponse { decimals })
+}
+
+// settings for the migration pages
+const MIGRATION_DEFAULT_LIMIT: u32 = 30;
+const MIGRATION_MAX_LIMIT: u32 = 100;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    // the migration is authorized by the admin, so no deposit proves the denoms exist
+    for native_token in msg.native_token_decimals.iter() {
+        NATIVE_TOKEN_DECIMALS.save(
+            deps.storage,
+            native_token.denom.as_bytes(),
+            &native_token.decimals,
+        )?;
+    }
+
+    let start_after = msg
+        .start_after
+        .map(|asset_infos| -> StdResult<_> {
+            Ok([
+                asset_infos[0].to_raw(deps.api)?,
+                asset_infos[1].to_raw(deps.api)?,
+            ])
+        })
+        .transpose()?;
+    let limit = msg
+        .limit
+        .unwrap_or(MIGRATION_DEFAULT_LIMIT)
+        .min(MIGRATION_MAX_LIMIT) as usize;
+
+    // re-save the pairs to build the indexes of the pairs stored before them
+    let stored_pairs = read_stored_pairs(deps.storage, start_after, limit)?;
+    let mut skipped_pairs: Vec<String> = vec![];
+    for (key, pair_info) in stored_pairs.iter() {
+        // the pairs stored before the decimals registry have no decimals,
+        // they keep none until the decimals of both assets are known
+        let mut pair_info = pair_info.clone();
+        if pair_info.asset_decimals == [0u8, 0u8] {
+            let decimals = [
+                query_decimals(
+                    deps.as_ref(),
+                    &pair_info.asset_infos[0].to_normal(deps.api)?,
+                ),
+                query_decimals(
+                    deps.as_ref(),
+                    &pair_info.asset_infos[1].to_normal(deps.api)?,
+                ),
+            ];
+            match decimals {
+                [Ok(decimals_0), Ok(decimals_1)] => {
+                    pair_info.asset_decimals = [decimals_0, decimals_1]
+                }
+                _ => skipped_pairs.push(
+                    deps.api
+                        .addr_humanize(&pair_info.contract_addr)?
+                        .to_string(),
+                ),
+            }
+        }
+        pairs().save(deps.storage, key, &pair_info)?;
+
+        // the pairs stored before the registration sequence are appended in the key order
+        if !PAIR_CREATION_INDEX.has(deps.storage, pair_info.contract_addr.as_slice()) {
+            append_pair_creation(deps.storage, &pair_info.contract_addr)?;
+            let count = PAIR_COUNT.may_load(deps.storage)?.unwrap_or_default();
+            PAIR_COUNT.save(deps.storage, &(count + 1))?;
+        }
+    }
+
+    let last_pair = match stored_pairs.last() {
+        Some((_, pair_info)) => deps
+            .api
+            .addr_humanize(&pair_info.contract_addr)?
+            .to_string(),
+        None => String::new(),
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "migrate"),
+        ("migrated_pairs", &stored_pairs.len().to_string()),
+        ("skipped_pairs", &skipped_pairs.join(",")),
+        ("last_pair", &last_pair),
+    ]))
 }
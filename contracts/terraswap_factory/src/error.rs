@@ -0,0 +1,71 @@
+use cosmwasm_std::{OverflowError, StdError};
+use terraswap::ownership::OwnershipError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("unauthorized")]
+    Unauthorized {},
+
+    #[error("Pair already exists")]
+    PairExists {},
+
+    #[error("no ownership proposal")]
+    NoOwnershipProposal {},
+
+    #[error("ownership proposal expired")]
+    OwnershipProposalExpired {},
+
+    #[error("expires_in must not exceed {max} seconds")]
+    InvalidExpiresIn { max: u64 },
+
+    #[error("creator is not allowed")]
+    CreatorNotAllowed {},
+
+    #[error("asset is not approved: {asset}")]
+    AssetNotApproved { asset: String },
+
+    #[error("pair creation fee must be a non-zero native token amount")]
+    InvalidCreationFee {},
+
+    #[error("asset is not in the pair: {asset}")]
+    AssetNotInPair { asset: String },
+
+    #[error("initial liquidity must be non-zero amounts of both assets")]
+    InvalidInitialLiquidity {},
+
+    #[error("funds other than the creation fee and the provided assets are not accepted: {denom}")]
+    UnexpectedFunds { denom: String },
+
+    #[error("a deposit of {denom} is required to prove it exists")]
+    NativeTokenDepositRequired { denom: String },
+
+    #[error("decimals of {denom} are not registered")]
+    NativeTokenDecimalsNotFound { denom: String },
+
+    #[error("unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("pair instantiation failed: {reason}")]
+    PairInstantiationFailed { reason: String },
+}
+
+impl From<OwnershipError> for ContractError {
+    fn from(err: OwnershipError) -> Self {
+        match err {
+            OwnershipError::Std(err) => ContractError::Std(err),
+            OwnershipError::Unauthorized {} => ContractError::Unauthorized {},
+            OwnershipError::NoOwnershipProposal {} => ContractError::NoOwnershipProposal {},
+            OwnershipError::OwnershipProposalExpired {} => {
+                ContractError::OwnershipProposalExpired {}
+            }
+            OwnershipError::InvalidExpiresIn { max } => ContractError::InvalidExpiresIn { max },
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_slice, to_binary, Api, Coin, ContractResult, Empty, OwnedDeps, Querier, QuerierResult,
-    QueryRequest, SystemError, SystemResult, WasmQuery,
+    from_binary, from_slice, to_binary, Api, Coin, ContractResult, Decimal, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use cosmwasm_storage::to_length_prefixed;
+use cw20::{Cw20QueryMsg, TokenInfoResponse};
 use std::collections::HashMap;
+use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
 use terraswap::asset::{AssetInfoRaw, PairInfo, PairInfoRaw};
+use terraswap::pair::{PoolResponse, QueryMsg as PairQueryMsg};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -23,8 +26,11 @@ pub fn mock_dependencies(
 }
 
 pub struct WasmMockQuerier {
-    base: MockQuerier<Empty>,
+    base: MockQuerier<TerraQueryWrapper>,
     terraswap_pair_querier: TerraswapPairQuerier,
+    tax_querier: TaxQuerier,
+    token_querier: TokenQuerier,
+    pool_querier: PoolQuerier,
 }
 
 #[derive(Clone, Default)]
@@ -48,10 +54,60 @@ pub(crate) fn pairs_to_map(pairs: &[(&String, &PairInfo)]) -> HashMap<String, Pa
     pairs_map
 }
 
+#[derive(Clone, Default)]
+pub struct TokenQuerier {
+    decimals: HashMap<String, u8>,
+}
+
+impl TokenQuerier {
+    pub fn new(decimals: &[(&String, &u8)]) -> Self {
+        TokenQuerier {
+            decimals: decimals
+                .iter()
+                .map(|(contract_addr, decimals)| (contract_addr.to_string(), **decimals))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct PoolQuerier {
+    pools: HashMap<String, PoolResponse>,
+}
+
+impl PoolQuerier {
+    pub fn new(pools: &[(&String, &PoolResponse)]) -> Self {
+        PoolQuerier {
+            pools: pools
+                .iter()
+                .map(|(contract_addr, pool)| (contract_addr.to_string(), (*pool).clone()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct TaxQuerier {
+    rate: Decimal,
+    caps: HashMap<String, Uint128>,
+}
+
+impl TaxQuerier {
+    pub fn new(rate: Decimal, caps: &[(&String, &Uint128)]) -> Self {
+        TaxQuerier {
+            rate,
+            caps: caps
+                .iter()
+                .map(|(denom, cap)| (denom.to_string(), **cap))
+                .collect(),
+        }
+    }
+}
+
 impl Querier for WasmMockQuerier {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
         // MockQuerier doesn't support Custom, so we ignore it completely here
-        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+        let request: QueryRequest<TerraQueryWrapper> = match from_slice(bin_request) {
             Ok(v) => v,
             Err(e) => {
                 return SystemResult::Err(SystemError::InvalidRequest {
@@ -65,8 +121,33 @@ impl Querier for WasmMockQuerier {
 }
 
 impl WasmMockQuerier {
-    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+    pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
         match &request {
+            QueryRequest::Custom(TerraQueryWrapper { route, query_data }) => {
+                if route == &TerraRoute::Treasury {
+                    match query_data {
+                        TerraQuery::TaxRate {} => {
+                            let res = TaxRateResponse {
+                                rate: self.tax_querier.rate,
+                            };
+                            SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                        }
+                        TerraQuery::TaxCap { denom } => {
+                            let cap = self
+                                .tax_querier
+                                .caps
+                                .get(denom)
+                                .copied()
+                                .unwrap_or_default();
+                            let res = TaxCapResponse { cap };
+                            SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                        }
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
+                } else {
+                    panic!("DO NOT ENTER HERE")
+                }
+            }
             QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key }) => {
                 let key: &[u8] = key.as_slice();
                 let prefix_pair_info = to_length_prefixed(b"pair_info").to_vec();
@@ -99,24 +180,79 @@ impl WasmMockQuerier {
                                 denom: "uusd".to_string(),
                             },
                         ],
+                        asset_decimals: [6u8, 6u8],
                     })))
                 } else {
                     panic!("DO NOT ENTER HERE")
                 }
             }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                if let Ok(PairQueryMsg::Pool {}) = from_binary(msg) {
+                    return match self.pool_querier.pools.get(contract_addr) {
+                        Some(pool) => SystemResult::Ok(ContractResult::from(to_binary(pool))),
+                        None => SystemResult::Err(SystemError::InvalidRequest {
+                            error: format!("No pool exists for the contract {}", contract_addr),
+                            request: msg.as_slice().into(),
+                        }),
+                    };
+                }
+
+                match from_binary(msg).unwrap() {
+                    Cw20QueryMsg::TokenInfo {} => {
+                        let decimals = match self.token_querier.decimals.get(contract_addr) {
+                            Some(decimals) => *decimals,
+                            None => {
+                                return SystemResult::Err(SystemError::InvalidRequest {
+                                    error: format!(
+                                        "No token info exists for the contract {}",
+                                        contract_addr
+                                    ),
+                                    request: msg.as_slice().into(),
+                                })
+                            }
+                        };
+
+                        SystemResult::Ok(ContractResult::from(to_binary(&TokenInfoResponse {
+                            name: "mAAPL".to_string(),
+                            symbol: "mAAPL".to_string(),
+                            decimals,
+                            total_supply: Uint128::zero(),
+                        })))
+                    }
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
             _ => self.base.handle_query(request),
         }
     }
 }
 
 impl WasmMockQuerier {
-    pub fn new(base: MockQuerier<Empty>) -> Self {
+    pub fn new(base: MockQuerier<TerraQueryWrapper>) -> Self {
         WasmMockQuerier {
             base,
             terraswap_pair_querier: TerraswapPairQuerier::default(),
+            tax_querier: TaxQuerier::default(),
+            token_querier: TokenQuerier::default(),
+            pool_querier: PoolQuerier::default(),
         }
     }
 
+    // configure the pools of the pairs
+    pub fn with_pools(&mut self, pools: &[(&String, &PoolResponse)]) {
+        self.pool_querier = PoolQuerier::new(pools);
+    }
+
+    // configure the decimals of the cw20 tokens
+    pub fn with_token_decimals(&mut self, decimals: &[(&String, &u8)]) {
+        self.token_querier = TokenQuerier::new(decimals);
+    }
+
+    // configure the tax rate and caps
+    pub fn with_tax(&mut self, rate: Decimal, caps: &[(&String, &Uint128)]) {
+        self.tax_querier = TaxQuerier::new(rate, caps);
+    }
+
     // configure the terraswap pair
     pub fn with_terraswap_pairs(&mut self, pairs: &[(&String, &PairInfo)]) {
         self.terraswap_pair_querier = TerraswapPairQuerier::new(pairs);
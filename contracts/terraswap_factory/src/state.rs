@@ -1,28 +1,180 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Api, CanonicalAddr, Order, StdResult, Storage};
-use cw_storage_plus::{Bound, Item, Map};
-use terraswap::asset::{AssetInfoRaw, PairInfo, PairInfoRaw};
+use cosmwasm_std::{Api, CanonicalAddr, Decimal, Order, StdError, StdResult, Storage};
+use cw_storage_plus::{
+    Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, U64Key, UniqueIndex,
+};
+use terraswap::asset::{AssetInfo, AssetInfoRaw, AssetRaw, PairInfo, PairInfoRaw};
+use terraswap::factory::PairCreationMode;
+use terraswap::ownership::{Owned, OwnershipProposal};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub owner: CanonicalAddr,
+    /// None once the ownership is renounced
+    pub owner: Option<CanonicalAddr>,
     pub pair_code_id: u64,
     pub token_code_id: u64,
+    /// Missing in the configs stored before the creation modes
+    #[serde(default)]
+    pub pair_creation_mode: PairCreationMode,
 }
 
 // put the length bytes at the first for compatibility with legacy singleton store
 pub const CONFIG: Item<Config> = Item::new("\u{0}\u{6}config");
 
+impl Owned for Config {
+    fn owner(&self) -> Option<&CanonicalAddr> {
+        self.owner.as_ref()
+    }
+
+    fn set_owner(&mut self, owner: Option<CanonicalAddr>) {
+        self.owner = owner;
+    }
+}
+
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// Pair creation waiting for the reply of its instantiation
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TmpPairInfo {
     pub pair_key: Vec<u8>,
     pub asset_infos: [AssetInfoRaw; 2],
+    pub asset_decimals: [u8; 2],
+    /// Initial liquidity to provide once the pair is instantiated
+    pub provide_info: Option<TmpProvideInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TmpProvideInfo {
+    pub assets: [AssetRaw; 2],
+    pub slippage_tolerance: Option<Decimal>,
+    pub receiver: CanonicalAddr,
+}
+
+/// Pending pair creations keyed by the reply id of their instantiate submessage
+pub const PENDING_PAIRS: Map<U64Key, TmpPairInfo> = Map::new("pending_pairs");
+pub const NEXT_REPLY_ID: Item<u64> = Item::new("next_reply_id");
+
+/// Reserves a fresh reply id, so that several creations in one transaction don't collide
+pub fn next_reply_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let id = NEXT_REPLY_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_REPLY_ID.save(storage, &(id + 1))?;
+    Ok(id)
+}
+
+pub struct PairIndexes<'a> {
+    pub address: UniqueIndex<'a, Vec<u8>, PairInfoRaw>,
+    pub liquidity_token: UniqueIndex<'a, Vec<u8>, PairInfoRaw>,
+    // a pair is indexed by each of its assets
+    pub asset_0: MultiIndex<'a, (Vec<u8>, Vec<u8>), PairInfoRaw>,
+    pub asset_1: MultiIndex<'a, (Vec<u8>, Vec<u8>), PairInfoRaw>,
+}
+
+impl<'a> IndexList<PairInfoRaw> for PairIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PairInfoRaw>> + '_> {
+        let v: Vec<&dyn Index<PairInfoRaw>> = vec![
+            &self.address,
+            &self.liquidity_token,
+            &self.asset_0,
+            &self.asset_1,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Pairs keyed by `pair_key`, indexed by the pair address, the LP token and the assets
+pub fn pairs<'a>() -> IndexedMap<'a, &'a [u8], PairInfoRaw, PairIndexes<'a>> {
+    let indexes = PairIndexes {
+        address: UniqueIndex::new(|pair| pair.contract_addr.to_vec(), "pair_info__address"),
+        liquidity_token: UniqueIndex::new(
+            |pair| pair.liquidity_token.to_vec(),
+            "pair_info__liquidity_token",
+        ),
+        asset_0: MultiIndex::new(
+            |pair, pk| (pair.asset_infos[0].as_bytes().to_vec(), pk),
+            "pair_info",
+            "pair_info__asset_0",
+        ),
+        asset_1: MultiIndex::new(
+            |pair, pk| (pair.asset_infos[1].as_bytes().to_vec(), pk),
+            "pair_info",
+            "pair_info__asset_1",
+        ),
+    };
+
+    IndexedMap::new("pair_info", indexes)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RetiredPair {
+    pub pair_info: PairInfoRaw,
+    pub retired_at: u64,
+}
+
+/// Deregistered pairs, keyed by the canonical pair address
+pub const RETIRED_PAIRS: Map<&[u8], RetiredPair> = Map::new("retired_pairs");
+
+/// Creators allowed in `Allowlist` mode, keyed by the canonical address
+pub const ALLOWED_CREATORS: Map<&[u8], bool> = Map::new("allowed_creators");
+/// Assets allowed in `ApprovedTokens` mode, keyed by the raw asset info bytes
+pub const APPROVED_TOKENS: Map<&[u8], AssetInfoRaw> = Map::new("approved_tokens");
+/// Decimals of the native tokens, keyed by the denom
+pub const NATIVE_TOKEN_DECIMALS: Map<&[u8], u8> = Map::new("native_token_decimals");
+
+/// Number of the registered pairs
+pub const PAIR_COUNT: Item<u64> = Item::new("pair_count");
+pub const NEXT_PAIR_INDEX: Item<u64> = Item::new("next_pair_index");
+/// Pair contract addresses keyed by their registration sequence number
+pub const PAIRS_BY_CREATION: Map<U64Key, CanonicalAddr> = Map::new("pairs_by_creation");
+/// Registration sequence number of the pairs, keyed by the pair contract address
+pub const PAIR_CREATION_INDEX: Map<&[u8], u64> = Map::new("pair_creation_index");
+
+/// Stores the pair and appends it to the registration sequence
+pub fn add_pair(
+    storage: &mut dyn Storage,
+    pair_key: &[u8],
+    pair_info: &PairInfoRaw,
+) -> StdResult<u64> {
+    pairs().save(storage, pair_key, pair_info)?;
+    let index = append_pair_creation(storage, &pair_info.contract_addr)?;
+
+    let count = PAIR_COUNT.may_load(storage)?.unwrap_or_default();
+    PAIR_COUNT.save(storage, &(count + 1))?;
+
+    Ok(index)
 }
 
-pub const TMP_PAIR_INFO: Item<TmpPairInfo> = Item::new("tmp_pair_info");
-pub const PAIRS: Map<&[u8], PairInfoRaw> = Map::new("pair_info");
+/// Assigns the next registration sequence number to the pair contract
+pub fn append_pair_creation(
+    storage: &mut dyn Storage,
+    contract_addr: &CanonicalAddr,
+) -> StdResult<u64> {
+    let index = NEXT_PAIR_INDEX.may_load(storage)?.unwrap_or(1);
+    NEXT_PAIR_INDEX.save(storage, &(index + 1))?;
+    PAIRS_BY_CREATION.save(storage, U64Key::new(index), contract_addr)?;
+    PAIR_CREATION_INDEX.save(storage, contract_addr.as_slice(), &index)?;
+
+    Ok(index)
+}
+
+/// Removes the pair and its registration sequence number
+pub fn remove_pair(
+    storage: &mut dyn Storage,
+    pair_key: &[u8],
+    pair_info: &PairInfoRaw,
+) -> StdResult<()> {
+    pairs().remove(storage, pair_key)?;
+
+    let contract_addr = pair_info.contract_addr.as_slice();
+    if let Some(index) = PAIR_CREATION_INDEX.may_load(storage, contract_addr)? {
+        PAIRS_BY_CREATION.remove(storage, U64Key::new(index));
+        PAIR_CREATION_INDEX.remove(storage, contract_addr);
+    }
+
+    let count = PAIR_COUNT.may_load(storage)?.unwrap_or_default();
+    PAIR_COUNT.save(storage, &count.saturating_sub(1))
+}
 
 pub fn pair_key(asset_infos: &[AssetInfoRaw; 2]) -> Vec<u8> {
     let mut asset_infos = asset_infos.to_vec();
@@ -43,7 +195,7 @@ pub fn read_pairs(
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = calc_range_start(start_after).map(Bound::exclusive);
 
-    PAIRS
+    pairs()
         .range(storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| {
@@ -53,6 +205,133 @@ pub fn read_pairs(
         .collect::<StdResult<Vec<PairInfo>>>()
 }
 
+/// Stored pairs with their keys, ordered by `pair_key`
+pub fn read_stored_pairs(
+    storage: &dyn Storage,
+    start_after: Option<[AssetInfoRaw; 2]>,
+    limit: usize,
+) -> StdResult<Vec<(Vec<u8>, PairInfoRaw)>> {
+    let start = calc_range_start(start_after).map(Bound::exclusive);
+
+    pairs()
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+/// Pairs containing the asset, ordered by `pair_key`
+pub fn read_pairs_by_asset(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    asset_info: &AssetInfoRaw,
+    start_after: Option<[AssetInfoRaw; 2]>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|asset_infos| pair_key(&asset_infos));
+    let pairs = pairs();
+
+    let mut items: Vec<(Vec<u8>, PairInfoRaw)> = vec![];
+    for index in [&pairs.idx.asset_0, &pairs.idx.asset_1].iter() {
+        let start = start_after.clone().map(Bound::exclusive);
+        for item in index
+            .prefix(asset_info.as_bytes().to_vec())
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+        {
+            items.push(item?);
+        }
+    }
+
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+    items
+        .into_iter()
+        .take(limit)
+        .map(|(_, v)| v.to_normal(api))
+        .collect::<StdResult<Vec<PairInfo>>>()
+}
+
+/// Pairs with their registration sequence numbers, in the order of the registration
+pub fn read_pairs_by_creation(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after_index: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, PairInfo)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after_index.map(Bound::exclusive_int);
+
+    PAIRS_BY_CREATION
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, contract_addr) = item?;
+            let (_, pair_info) = pairs()
+                .idx
+                .address
+                .item(storage, contract_addr.to_vec())?
+                .ok_or_else(|| StdError::not_found("PairInfoRaw"))?;
+
+            let mut index = [0u8; 8];
+            index.copy_from_slice(&k);
+            Ok((u64::from_be_bytes(index), pair_info.to_normal(api)?))
+        })
+        .collect::<StdResult<Vec<(u64, PairInfo)>>>()
+}
+
+pub fn read_retired_pairs(
+    storage: &dyn Storage,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<RetiredPair>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|addr| Bound::exclusive(addr.as_slice()));
+
+    RETIRED_PAIRS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            Ok(v)
+        })
+        .collect::<StdResult<Vec<RetiredPair>>>()
+}
+
+pub fn read_allowed_creators(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|addr| Bound::exclusive(addr.as_slice()));
+
+    ALLOWED_CREATORS
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| Ok(api.addr_humanize(&CanonicalAddr::from(key))?.to_string()))
+        .collect::<StdResult<Vec<String>>>()
+}
+
+pub fn read_approved_tokens(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<AssetInfoRaw>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AssetInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|asset_info| Bound::exclusive(asset_info.as_bytes()));
+
+    APPROVED_TOKENS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, v) = item?;
+            v.to_normal(api)
+        })
+        .collect::<StdResult<Vec<AssetInfo>>>()
+}
+
 // this will set the first key after the provided key, by appending a 1 byte
 fn calc_range_start(start_after: Option<[AssetInfoRaw; 2]>) -> Option<Vec<u8>> {
     start_after.map(|asset_infos| {
@@ -75,7 +354,7 @@ mod test {
     use cosmwasm_std::testing::mock_dependencies;
     use cosmwasm_std::{Api, StdResult, Storage};
     use cosmwasm_storage::{
-        bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket,
+        bucket, bucket_read, singleton, singleton_read, to_length_prefixed, Bucket, ReadonlyBucket,
     };
     const KEY_CONFIG: &[u8] = b"config";
 
@@ -92,9 +371,10 @@ mod test {
         store_config(
             &mut deps.storage,
             &Config {
-                owner: deps.api.addr_canonicalize("owner0000").unwrap(),
+                owner: Some(deps.api.addr_canonicalize("owner0000").unwrap()),
                 pair_code_id: 1,
                 token_code_id: 1,
+                pair_creation_mode: PairCreationMode::Permissionless {},
             },
         )
         .unwrap();
@@ -105,6 +385,20 @@ mod test {
         );
     }
 
+    #[test]
+    fn config_without_pair_creation_mode() {
+        let mut deps = mock_dependencies(&[]);
+        deps.storage.set(
+            &to_length_prefixed(KEY_CONFIG),
+            br#"{"owner":"b3duZXIwMDAw","pair_code_id":1,"token_code_id":1}"#,
+        );
+
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().pair_creation_mode,
+            PairCreationMode::Permissionless {}
+        );
+    }
+
     const PREFIX_PAIR_INFO: &[u8] = b"pair_info";
     pub fn store_pair(storage: &mut dyn Storage, data: &PairInfoRaw) -> StdResult<()> {
         let mut asset_infos = data.asset_infos.clone().to_vec();
@@ -160,6 +454,7 @@ mod test {
             ],
             contract_addr: deps.api.addr_canonicalize("pair0000").unwrap(),
             liquidity_token: deps.api.addr_canonicalize("liquidity0000").unwrap(),
+            asset_decimals: [6u8, 6u8],
         };
 
         let pair_info2 = PairInfoRaw {
@@ -173,20 +468,21 @@ mod test {
             ],
             contract_addr: deps.api.addr_canonicalize("pair0001").unwrap(),
             liquidity_token: deps.api.addr_canonicalize("liquidity0001").unwrap(),
+            asset_decimals: [6u8, 6u8],
         };
 
         store_pair(&mut deps.storage, &pair_info).unwrap();
         store_pair(&mut deps.storage, &pair_info2).unwrap();
 
         assert_eq!(
-            PAIRS
+            pairs()
                 .load(&deps.storage, &pair_key(&pair_info.asset_infos))
                 .unwrap(),
             read_pair(&deps.storage, &pair_info.asset_infos).unwrap()
         );
 
         assert_eq!(
-            PAIRS
+            pairs()
                 .load(&deps.storage, &pair_key(&pair_info2.asset_infos))
                 .unwrap(),
             read_pair(&deps.storage, &pair_info2.asset_infos).unwrap()
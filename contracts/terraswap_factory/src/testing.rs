@@ -1,16 +1,27 @@
-use crate::contract::{execute, instantiate, query, reply};
-use crate::mock_querier::mock_dependencies;
+use crate::contract::{execute, instantiate, migrate, query, reply};
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
 
-use crate::state::{pair_key, TmpPairInfo, TMP_PAIR_INFO};
+use crate::error::ContractError;
+use crate::state::{pair_key, TmpPairInfo, PENDING_PAIRS};
 
-use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, from_binary, to_binary, ContractResult, Reply, ReplyOn, StdError, SubMsg,
-    SubMsgExecutionResponse, WasmMsg,
+    attr, from_binary, to_binary, BankMsg, Coin, ContractResult, CosmosMsg, Decimal, Deps, DepsMut,
+    OwnedDeps, Reply, ReplyOn, StdError, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::{Map, U64Key};
+use terraswap::asset::{Asset, AssetInfo, PairInfo, PairInfoRaw};
+use terraswap::factory::{
+    AllowedCreatorsResponse, ApprovedTokensResponse, ConfigResponse, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, NativeTokenDecimals, NativeTokenDecimalsResponse, PairByCreationResponse,
+    PairCountResponse, PairCreationMode, PairsByCreationResponse, PairsResponse, QueryMsg,
+    RetiredPairResponse, RetiredPairsResponse,
+};
+use terraswap::ownership::{OwnershipProposalResponse, MAX_OWNERSHIP_PROPOSAL_TTL};
+use terraswap::pair::{
+    ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg, PairType, PoolResponse,
 };
-use terraswap::asset::{AssetInfo, PairInfo};
-use terraswap::factory::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use terraswap::pair::InstantiateMsg as PairInstantiateMsg;
 
 #[test]
 fn proper_initialization() {
@@ -30,7 +41,8 @@ fn proper_initialization() {
     let config_res: ConfigResponse = from_binary(&query_res).unwrap();
     assert_eq!(123u64, config_res.token_code_id);
     assert_eq!(321u64, config_res.pair_code_id);
-    assert_eq!("addr0000".to_string(), config_res.owner);
+    assert_eq!(Some("addr0000".to_string()), config_res.owner);
+    assert_eq!(None, config_res.ownership_proposal);
 }
 
 #[test]
@@ -49,29 +61,35 @@ fn update_config() {
 
     // update owner
     let info = mock_info("addr0000", &[]);
-    let msg = ExecuteMsg::UpdateConfig {
-        owner: Some("addr0001".to_string()),
-        pair_code_id: None,
-        token_code_id: None,
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: "addr0001".to_string(),
+        expires_in: 100u64,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(0, res.messages.len());
 
+    let info = mock_info("addr0001", &[]);
+    let msg = ExecuteMsg::ClaimOwnership {};
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(0, res.messages.len());
+
     // it worked, let's query the state
     let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
     let config_res: ConfigResponse = from_binary(&query_res).unwrap();
     assert_eq!(123u64, config_res.token_code_id);
     assert_eq!(321u64, config_res.pair_code_id);
-    assert_eq!("addr0001".to_string(), config_res.owner);
+    assert_eq!(Some("addr0001".to_string()), config_res.owner);
+    assert_eq!(None, config_res.ownership_proposal);
 
     // update left items
     let env = mock_env();
     let info = mock_info("addr0001", &[]);
     let msg = ExecuteMsg::UpdateConfig {
-        owner: None,
         pair_code_id: Some(100u64),
         token_code_id: Some(200u64),
+        pair_creation_mode: None,
     };
 
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
@@ -82,24 +100,360 @@ fn update_config() {
     let config_res: ConfigResponse = from_binary(&query_res).unwrap();
     assert_eq!(200u64, config_res.token_code_id);
     assert_eq!(100u64, config_res.pair_code_id);
-    assert_eq!("addr0001".to_string(), config_res.owner);
+    assert_eq!(Some("addr0001".to_string()), config_res.owner);
 
     // Unauthorized err
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
     let msg = ExecuteMsg::UpdateConfig {
-        owner: None,
         pair_code_id: None,
         token_code_id: None,
+        pair_creation_mode: None,
     };
 
     let res = execute(deps.as_mut(), env, info, msg);
     match res {
-        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("Must return unauthorized error"),
+    }
+}
+
+#[test]
+fn ownership_transfer() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_code_id: 321u64,
+        token_code_id: 123u64,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only the owner can propose
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: "addr0001".to_string(),
+        expires_in: 100u64,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ProposeNewOwner {
+            owner: "addr0001".to_string(),
+            expires_in: MAX_OWNERSHIP_PROPOSAL_TTL + 1,
+        },
+    );
+    match res {
+        Err(ContractError::InvalidExpiresIn { max }) => assert_eq!(max, 1209600),
+        _ => panic!("Must return invalid expires_in error"),
+    }
+
+    let env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    // the pending proposal is visible in the config
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(Some("addr0000".to_string()), config_res.owner);
+    assert_eq!(
+        Some(OwnershipProposalResponse {
+            owner: "addr0001".to_string(),
+            expires_at: env.block.time.seconds() + 100,
+        }),
+        config_res.ownership_proposal
+    );
+
+    // only the proposed owner can claim
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0002", &[]),
+        ExecuteMsg::ClaimOwnership {},
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    // expired proposal cannot be claimed
+    let mut expired_env = mock_env();
+    expired_env.block.time = expired_env.block.time.plus_seconds(100);
+    let res = execute(
+        deps.as_mut(),
+        expired_env,
+        mock_info("addr0001", &[]),
+        ExecuteMsg::ClaimOwnership {},
+    );
+    match res {
+        Err(ContractError::OwnershipProposalExpired {}) => {}
+        _ => panic!("Must return ownership proposal expired error"),
+    }
+
+    // dropped proposal cannot be claimed
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::DropOwnershipProposal {},
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::ClaimOwnership {},
+    );
+    match res {
+        Err(ContractError::NoOwnershipProposal {}) => {}
+        _ => panic!("Must return no ownership proposal error"),
+    }
+
+    // renounce ownership
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RenounceOwnership {},
+    )
+    .unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert_eq!(None, config_res.owner);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateConfig {
+            pair_code_id: Some(100u64),
+            token_code_id: None,
+            pair_creation_mode: None,
+        },
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
         _ => panic!("Must return unauthorized error"),
     }
 }
 
+#[test]
+fn pair_creation_modes() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let msg = InstantiateMsg {
+        pair_code_id: 321u64,
+        token_code_id: 123u64,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    register_decimals(&mut deps);
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    ];
+    let create_pair_msg = ExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pair_type: None,
+    };
+
+    // allowlist
+    let update_mode = |deps: DepsMut, pair_creation_mode: PairCreationMode| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::UpdateConfig {
+                token_code_id: None,
+                pair_code_id: None,
+                pair_creation_mode: Some(pair_creation_mode),
+            },
+        )
+        .unwrap();
+    };
+    update_mode(deps.as_mut(), PairCreationMode::Allowlist {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        create_pair_msg.clone(),
+    );
+    match res {
+        Err(ContractError::CreatorNotAllowed {}) => {}
+        _ => panic!("Must return creator not allowed error"),
+    }
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateAllowedCreators {
+            add: vec!["addr0001".to_string(), "addr0002".to_string()],
+            remove: vec!["addr0002".to_string()],
+        },
+    )
+    .unwrap();
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllowedCreators {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let creators_res: AllowedCreatorsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(creators_res.creators, vec!["addr0001".to_string()]);
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        create_pair_msg.clone(),
+    )
+    .unwrap();
+
+    // approved tokens
+    update_mode(deps.as_mut(), PairCreationMode::ApprovedTokens {});
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateApprovedTokens {
+            add: vec![asset_infos[1].clone()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        create_pair_msg.clone(),
+    );
+    match res {
+        Err(ContractError::AssetNotApproved { asset }) => assert_eq!(asset, "asset0000"),
+        _ => panic!("Must return asset not approved error"),
+    }
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateApprovedTokens {
+            add: vec![asset_infos[0].clone()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ApprovedTokens {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let tokens_res: ApprovedTokensResponse = from_binary(&query_res).unwrap();
+    assert_eq!(tokens_res.asset_infos.len(), 2);
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0003", &[]),
+        create_pair_msg.clone(),
+    )
+    .unwrap();
+
+    // creation fee
+    update_mode(
+        deps.as_mut(),
+        PairCreationMode::Fee {
+            fee: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(1010u128),
+            },
+            fee_collector: "collector0000".to_string(),
+        },
+    );
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&query_res).unwrap();
+    assert!(matches!(
+        config_res.pair_creation_mode,
+        PairCreationMode::Fee { .. }
+    ));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0003", &[]),
+        create_pair_msg.clone(),
+    );
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(
+            msg,
+            "Native token balance mismatch between the argument and the transferred"
+        ),
+        _ => panic!("Must return generic error"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0003",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1010u128),
+            }],
+        ),
+        create_pair_msg,
+    )
+    .unwrap();
+
+    // the fee is forwarded after the tax
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "collector0000".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+        }))
+    );
+}
+
 #[test]
 fn create_pair() {
     let mut deps = mock_dependencies(&[]);
@@ -115,6 +469,12 @@ fn create_pair() {
     // we can just call .unwrap() to assert this was a success
     let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
 
+    deps.querier.with_token_decimals(&[
+        (&"asset0000".to_string(), &8u8),
+        (&"asset0001".to_string(), &6u8),
+        (&"asset0002".to_string(), &18u8),
+    ]);
+
     let asset_infos = [
         AssetInfo::Token {
             contract_addr: "asset0000".to_string(),
@@ -126,6 +486,7 @@ fn create_pair() {
 
     let msg = ExecuteMsg::CreatePair {
         asset_infos: asset_infos.clone(),
+        pair_type: None,
     };
 
     let env = mock_env();
@@ -143,11 +504,13 @@ fn create_pair() {
         vec![SubMsg {
             id: 1,
             gas_limit: None,
-            reply_on: ReplyOn::Success,
+            reply_on: ReplyOn::Always,
             msg: WasmMsg::Instantiate {
                 msg: to_binary(&PairInstantiateMsg {
                     asset_infos: asset_infos.clone(),
+                    asset_decimals: [8u8, 6u8],
                     token_code_id: 123u64,
+                    pair_type: None,
                 })
                 .unwrap(),
                 code_id: 321u64,
@@ -165,12 +528,115 @@ fn create_pair() {
     ];
 
     assert_eq!(
-        TMP_PAIR_INFO.load(&deps.storage).unwrap(),
+        PENDING_PAIRS.load(&deps.storage, U64Key::new(1)).unwrap(),
         TmpPairInfo {
             asset_infos: raw_infos.clone(),
+            asset_decimals: [8u8, 6u8],
             pair_key: pair_key(&raw_infos),
+            provide_info: None,
         }
     );
+
+    // the next creation is pending under another reply id
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0002".to_string(),
+        },
+    ];
+
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pair_type: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(res.messages[0].id, 2);
+
+    let raw_infos = [
+        asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+        asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+    ];
+
+    assert_eq!(
+        PENDING_PAIRS
+            .load(&deps.storage, U64Key::new(2))
+            .unwrap()
+            .pair_key,
+        pair_key(&raw_infos),
+    );
+}
+
+#[test]
+fn create_oracle_pair() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_code_id: 321u64,
+        token_code_id: 123u64,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_token_decimals(&[
+        (&"asset0000".to_string(), &8u8),
+        (&"asset0001".to_string(), &6u8),
+    ]);
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+    ];
+    let pair_type = PairType::Oracle {
+        oracle: "oracle0000".to_string(),
+        max_age: 60,
+        spread: Decimal::permille(1),
+        skew_fee_rate: Decimal::percent(1),
+    };
+
+    let msg = ExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pair_type: Some(pair_type.clone()),
+    };
+
+    // only the owner can create an oracle pair
+    let info = mock_info("addr0001", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg {
+            id: 1,
+            gas_limit: None,
+            reply_on: ReplyOn::Always,
+            msg: WasmMsg::Instantiate {
+                msg: to_binary(&PairInstantiateMsg {
+                    asset_infos,
+                    asset_decimals: [8u8, 6u8],
+                    token_code_id: 123u64,
+                    pair_type: Some(pair_type),
+                })
+                .unwrap(),
+                code_id: 321u64,
+                funds: vec![],
+                label: "".to_string(),
+                admin: None,
+            }
+            .into()
+        },]
+    );
 }
 
 #[test]
@@ -192,12 +658,15 @@ fn reply_test() {
     ];
 
     let pair_key = pair_key(&raw_infos);
-    TMP_PAIR_INFO
+    PENDING_PAIRS
         .save(
             &mut deps.storage,
+            U64Key::new(1),
             &TmpPairInfo {
                 asset_infos: raw_infos,
+                asset_decimals: [6u8, 6u8],
                 pair_key,
+                provide_info: None,
             },
         )
         .unwrap();
@@ -224,6 +693,7 @@ fn reply_test() {
             ],
             contract_addr: "pair0000".to_string(),
             liquidity_token: "liquidity0000".to_string(),
+            asset_decimals: [6u8, 6u8],
         },
     )]);
 
@@ -245,6 +715,1132 @@ fn reply_test() {
             liquidity_token: "liquidity0000".to_string(),
             contract_addr: "pair0000".to_string(),
             asset_infos,
+            asset_decimals: [6u8, 6u8],
         }
     );
+
+    // the pending creation is cleaned up
+    assert!(PENDING_PAIRS
+        .may_load(&deps.storage, U64Key::new(1))
+        .unwrap()
+        .is_none());
+
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(vec![10, 8, 112, 97, 105, 114, 48, 48, 48, 48].into()),
+        }),
+    };
+
+    let res = reply(deps.as_mut(), mock_env(), reply_msg);
+    match res {
+        Err(ContractError::UnknownReplyId { id }) => assert_eq!(id, 1),
+        _ => panic!("Must return unknown reply id error"),
+    }
+}
+
+#[test]
+fn reply_instantiation_failed() {
+    let mut deps = mock_dependencies(&[]);
+
+    let raw_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        }
+        .to_raw(deps.as_ref().api)
+        .unwrap(),
+        AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        }
+        .to_raw(deps.as_ref().api)
+        .unwrap(),
+    ];
+
+    PENDING_PAIRS
+        .save(
+            &mut deps.storage,
+            U64Key::new(3),
+            &TmpPairInfo {
+                pair_key: pair_key(&raw_infos),
+                asset_infos: raw_infos,
+                asset_decimals: [6u8, 6u8],
+                provide_info: None,
+            },
+        )
+        .unwrap();
+
+    let reply_msg = Reply {
+        id: 3,
+        result: ContractResult::Err("out of gas".to_string()),
+    };
+
+    let res = reply(deps.as_mut(), mock_env(), reply_msg);
+    match res {
+        Err(ContractError::PairInstantiationFailed { reason }) => assert_eq!(reason, "out of gas"),
+        _ => panic!("Must return pair instantiation failed error"),
+    }
+}
+
+#[test]
+fn create_pair_and_provide() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let msg = InstantiateMsg {
+        pair_code_id: 321u64,
+        token_code_id: 123u64,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    register_decimals(&mut deps);
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    ];
+    let assets = [
+        Asset {
+            info: asset_infos[1].clone(),
+            amount: Uint128::from(1010u128),
+        },
+        Asset {
+            info: asset_infos[0].clone(),
+            amount: Uint128::from(2000u128),
+        },
+    ];
+
+    // native tokens must be sent
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreatePairAndProvide {
+            asset_infos: asset_infos.clone(),
+            pair_type: None,
+            assets: assets.clone(),
+            slippage_tolerance: None,
+            receiver: None,
+        },
+    );
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(
+            msg,
+            "Native token balance mismatch between the argument and the transferred"
+        ),
+        _ => panic!("Must return generic error"),
+    }
+
+    // funds beyond the provided assets are rejected
+    for funds in [
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1011u128),
+        }],
+        vec![
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::from(1u128),
+            },
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1010u128),
+            },
+        ],
+    ] {
+        let denom = funds[0].denom.clone();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &funds),
+            ExecuteMsg::CreatePairAndProvide {
+                asset_infos: asset_infos.clone(),
+                pair_type: None,
+                assets: assets.clone(),
+                slippage_tolerance: None,
+                receiver: None,
+            },
+        );
+        assert_eq!(res, Err(ContractError::UnexpectedFunds { denom }));
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1010u128),
+            }],
+        ),
+        ExecuteMsg::CreatePairAndProvide {
+            asset_infos: asset_infos.clone(),
+            pair_type: None,
+            assets,
+            slippage_tolerance: Some(Decimal::percent(1)),
+            receiver: Some("addr0001".to_string()),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr0000".to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::from(2000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg {
+                id: 1,
+                gas_limit: None,
+                reply_on: ReplyOn::Always,
+                msg: WasmMsg::Instantiate {
+                    msg: to_binary(&PairInstantiateMsg {
+                        asset_infos: asset_infos.clone(),
+                        asset_decimals: [6u8, 6u8],
+                        token_code_id: 123u64,
+                        pair_type: None,
+                    })
+                    .unwrap(),
+                    code_id: 321u64,
+                    funds: vec![],
+                    label: "".to_string(),
+                    admin: None,
+                }
+                .into()
+            },
+        ]
+    );
+
+    // register terraswap pair querier
+    deps.querier.with_terraswap_pairs(&[(
+        &"pair0000".to_string(),
+        &PairInfo {
+            asset_infos: asset_infos.clone(),
+            contract_addr: "pair0000".to_string(),
+            liquidity_token: "liquidity0000".to_string(),
+            asset_decimals: [6u8, 6u8],
+        },
+    )]);
+
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(vec![10, 8, 112, 97, 105, 114, 48, 48, 48, 48].into()),
+        }),
+    };
+
+    // the pair gets the allowance and the native tokens after the tax
+    let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: "pair0000".to_string(),
+                    amount: Uint128::from(2000u128),
+                    expires: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "pair0000".to_string(),
+                msg: to_binary(&PairExecuteMsg::ProvideLiquidity {
+                    assets: [
+                        Asset {
+                            info: asset_infos[1].clone(),
+                            amount: Uint128::from(1000u128),
+                        },
+                        Asset {
+                            info: asset_infos[0].clone(),
+                            amount: Uint128::from(2000u128),
+                        },
+                    ],
+                    slippage_tolerance: Some(Decimal::percent(1)),
+                    receiver: Some("addr0001".to_string()),
+                })
+                .unwrap(),
+                funds: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(1000u128),
+                }],
+            }),
+        ]
+    );
+}
+
+#[test]
+fn pair_reverse_lookups() {
+    let mut deps = mock_dependencies(&[]);
+
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let pair_infos = [
+        PairInfo {
+            asset_infos: [
+                AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                uusd.clone(),
+            ],
+            contract_addr: "pair0000".to_string(),
+            liquidity_token: "liquidity0000".to_string(),
+            asset_decimals: [6u8, 6u8],
+        },
+        PairInfo {
+            asset_infos: [
+                uusd.clone(),
+                AssetInfo::Token {
+                    contract_addr: "asset0001".to_string(),
+                },
+            ],
+            contract_addr: "pair0001".to_string(),
+            liquidity_token: "liquidity0001".to_string(),
+            asset_decimals: [6u8, 6u8],
+        },
+        PairInfo {
+            asset_infos: [
+                AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                AssetInfo::Token {
+                    contract_addr: "asset0001".to_string(),
+                },
+            ],
+            contract_addr: "pair0002".to_string(),
+            liquidity_token: "liquidity0002".to_string(),
+            asset_decimals: [6u8, 6u8],
+        },
+    ];
+
+    // pairs stored before the indexes are indexed by the migration
+    for pair_info in pair_infos.iter() {
+        let raw_infos = [
+            pair_info.asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+            pair_info.asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+        ];
+        let pair_info_raw = PairInfoRaw {
+            contract_addr: deps
+                .as_ref()
+                .api
+                .addr_canonicalize(&pair_info.contract_addr)
+                .unwrap(),
+            liquidity_token: deps
+                .as_ref()
+                .api
+                .addr_canonicalize(&pair_info.liquidity_token)
+                .unwrap(),
+            asset_infos: raw_infos.clone(),
+            asset_decimals: [6u8, 6u8],
+        };
+        Map::<&[u8], PairInfoRaw>::new("pair_info")
+            .save(&mut deps.storage, &pair_key(&raw_infos), &pair_info_raw)
+            .unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairByAddress {
+            address: "pair0001".to_string(),
+        },
+    );
+    assert!(res.is_err());
+
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            native_token_decimals: vec![],
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairByAddress {
+            address: "pair0001".to_string(),
+        },
+    )
+    .unwrap();
+    let pair_res: PairInfo = from_binary(&query_res).unwrap();
+    assert_eq!(pair_res, pair_infos[1]);
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairByLiquidityToken {
+            token: "liquidity0002".to_string(),
+        },
+    )
+    .unwrap();
+    let pair_res: PairInfo = from_binary(&query_res).unwrap();
+    assert_eq!(pair_res, pair_infos[2]);
+
+    // uusd is the second asset of one pair and the first of another
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairsByAsset {
+            asset_info: uusd.clone(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let pairs_res: PairsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(pairs_res.pairs.len(), 2);
+    assert!(pairs_res.pairs.contains(&pair_infos[0]));
+    assert!(pairs_res.pairs.contains(&pair_infos[1]));
+
+    // paginate
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairsByAsset {
+            asset_info: uusd,
+            start_after: Some(pairs_res.pairs[0].asset_infos.clone()),
+            limit: Some(1),
+        },
+    )
+    .unwrap();
+    let next_res: PairsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(next_res.pairs, vec![pairs_res.pairs[1].clone()]);
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairsByAsset {
+            asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let pairs_res: PairsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(pairs_res.pairs.len(), 2);
+    assert!(pairs_res.pairs.contains(&pair_infos[1]));
+    assert!(pairs_res.pairs.contains(&pair_infos[2]));
+}
+
+#[test]
+fn deregister_and_register_pair() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_code_id: 321u64,
+        token_code_id: 123u64,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    register_decimals(&mut deps);
+
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    ];
+    let pair_info = PairInfo {
+        asset_infos: asset_infos.clone(),
+        contract_addr: "pair0000".to_string(),
+        liquidity_token: "liquidity0000".to_string(),
+        asset_decimals: [6u8, 6u8],
+    };
+
+    // only the owner can register
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::RegisterPair {
+            pair_info: pair_info.clone(),
+        },
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RegisterPair {
+            pair_info: pair_info.clone(),
+        },
+    )
+    .unwrap();
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pair {
+            asset_infos: asset_infos.clone(),
+        },
+    )
+    .unwrap();
+    let pair_res: PairInfo = from_binary(&query_res).unwrap();
+    assert_eq!(pair_res, pair_info);
+
+    // the asset combination is taken
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreatePair {
+            asset_infos: asset_infos.clone(),
+            pair_type: None,
+        },
+    );
+    match res {
+        Err(ContractError::PairExists {}) => {}
+        _ => panic!("Must return pair exists error"),
+    }
+
+    // deregister
+    let env = mock_env();
+    let _res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::DeregisterPair {
+            asset_infos: asset_infos.clone(),
+        },
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pair {
+            asset_infos: asset_infos.clone(),
+        },
+    );
+    assert!(res.is_err());
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairByLiquidityToken {
+            token: "liquidity0000".to_string(),
+        },
+    );
+    assert!(res.is_err());
+
+    // the retired pair can still be resolved
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RetiredPair {
+            address: "pair0000".to_string(),
+        },
+    )
+    .unwrap();
+    let retired_res: RetiredPairResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        retired_res,
+        RetiredPairResponse {
+            pair_info: pair_info.clone(),
+            retired_at: env.block.time.seconds(),
+        }
+    );
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RetiredPairs {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let retired_res: RetiredPairsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(retired_res.pairs.len(), 1);
+
+    // the asset combination can be created again
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreatePair {
+            asset_infos,
+            pair_type: None,
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn add_native_token_decimals() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_code_id: 321u64,
+        token_code_id: 123u64,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    deps.querier
+        .with_token_decimals(&[(&"asset0000".to_string(), &8u8)]);
+
+    let msg = ExecuteMsg::AddNativeTokenDecimals {
+        denom: "uluna".to_string(),
+        decimals: 6u8,
+    };
+
+    // only the owner can register
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0001",
+            &[Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::from(1u128),
+            }],
+        ),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    // a deposit of the denom is required
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1u128),
+            }],
+        ),
+        msg.clone(),
+    );
+    match res {
+        Err(ContractError::NativeTokenDepositRequired { denom }) => assert_eq!(denom, "uluna"),
+        _ => panic!("Must return native token deposit required error"),
+    }
+
+    // pair of an unregistered native token cannot be created
+    let asset_infos = [
+        AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+    ];
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreatePair {
+            asset_infos: asset_infos.clone(),
+            pair_type: None,
+        },
+    );
+    match res {
+        Err(ContractError::NativeTokenDecimalsNotFound { denom }) => assert_eq!(denom, "uluna"),
+        _ => panic!("Must return native token decimals not found error"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::from(1u128),
+            }],
+        ),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "add_native_token_decimals"),
+            attr("denom", "uluna"),
+            attr("decimals", "6"),
+        ]
+    );
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NativeTokenDecimals {
+            denom: "uluna".to_string(),
+        },
+    )
+    .unwrap();
+    let decimals_res: NativeTokenDecimalsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(decimals_res.decimals, 6u8);
+
+    // the pair gets the decimals of both assets
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreatePair {
+            asset_infos: asset_infos.clone(),
+            pair_type: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Instantiate {
+            msg: to_binary(&PairInstantiateMsg {
+                asset_infos,
+                asset_decimals: [8u8, 6u8],
+                token_code_id: 123u64,
+                pair_type: None,
+            })
+            .unwrap(),
+            code_id: 321u64,
+            funds: vec![],
+            label: "".to_string(),
+            admin: None,
+        }
+        .into()
+    );
+}
+
+#[test]
+fn pair_count_and_creation_order() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_code_id: 321u64,
+        token_code_id: 123u64,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let pair_infos = [
+        PairInfo {
+            asset_infos: [
+                AssetInfo::Token {
+                    contract_addr: "asset0001".to_string(),
+                },
+                uusd.clone(),
+            ],
+            contract_addr: "pair0001".to_string(),
+            liquidity_token: "liquidity0001".to_string(),
+            asset_decimals: [6u8, 6u8],
+        },
+        PairInfo {
+            asset_infos: [
+                AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                uusd.clone(),
+            ],
+            contract_addr: "pair0000".to_string(),
+            liquidity_token: "liquidity0000".to_string(),
+            asset_decimals: [6u8, 6u8],
+        },
+        PairInfo {
+            asset_infos: [
+                AssetInfo::Token {
+                    contract_addr: "asset0002".to_string(),
+                },
+                uusd.clone(),
+            ],
+            contract_addr: "pair0002".to_string(),
+            liquidity_token: "liquidity0002".to_string(),
+            asset_decimals: [6u8, 6u8],
+        },
+    ];
+
+    // pairs stored before the registration sequence are appended in the key order
+    for pair_info in pair_infos[..2].iter() {
+        let raw_infos = [
+            pair_info.asset_infos[0].to_raw(deps.as_ref().api).unwrap(),
+            pair_info.asset_infos[1].to_raw(deps.as_ref().api).unwrap(),
+        ];
+        let pair_info_raw = PairInfoRaw {
+            contract_addr: deps
+                .as_ref()
+                .api
+                .addr_canonicalize(&pair_info.contract_addr)
+                .unwrap(),
+            liquidity_token: deps
+                .as_ref()
+                .api
+                .addr_canonicalize(&pair_info.liquidity_token)
+                .unwrap(),
+            asset_infos: raw_infos.clone(),
+            asset_decimals: [6u8, 6u8],
+        };
+        Map::<&[u8], PairInfoRaw>::new("pair_info")
+            .save(&mut deps.storage, &pair_key(&raw_infos), &pair_info_raw)
+            .unwrap();
+    }
+
+    let _res = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            native_token_decimals: vec![],
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+
+    let query_count = |deps: Deps| -> u64 {
+        let res: PairCountResponse =
+            from_binary(&query(deps, mock_env(), QueryMsg::PairCount {}).unwrap()).unwrap();
+        res.count
+    };
+    assert_eq!(query_count(deps.as_ref()), 2);
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RegisterPair {
+            pair_info: pair_infos[2].clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(query_count(deps.as_ref()), 3);
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairsByCreation {
+            start_after_index: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let pairs_res: PairsByCreationResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        pairs_res.pairs,
+        vec![
+            PairByCreationResponse {
+                index: 1,
+                pair_info: pair_infos[1].clone(),
+            },
+            PairByCreationResponse {
+                index: 2,
+                pair_info: pair_infos[0].clone(),
+            },
+            PairByCreationResponse {
+                index: 3,
+                pair_info: pair_infos[2].clone(),
+            },
+        ]
+    );
+
+    // a deregistered pair leaves the sequence
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::DeregisterPair {
+            asset_infos: pair_infos[0].asset_infos.clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(query_count(deps.as_ref()), 2);
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PairsByCreation {
+            start_after_index: Some(1),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let pairs_res: PairsByCreationResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        pairs_res.pairs,
+        vec![PairByCreationResponse {
+            index: 3,
+            pair_info: pair_infos[2].clone(),
+        }]
+    );
+
+    // pools are queried from the pairs
+    let pool = |amount: u128| PoolResponse {
+        assets: [
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: Uint128::from(amount),
+            },
+            Asset {
+                info: uusd.clone(),
+                amount: Uint128::from(amount),
+            },
+        ],
+        balances: [
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: Uint128::from(amount),
+            },
+            Asset {
+                info: uusd.clone(),
+                amount: Uint128::from(amount),
+            },
+        ],
+        total_share: Uint128::from(amount),
+    };
+    deps.querier.with_pools(&[
+        (&"pair0000".to_string(), &pool(100u128)),
+        (&"pair0002".to_string(), &pool(200u128)),
+    ]);
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pairs {
+            start_after: None,
+            limit: None,
+            include_pools: Some(true),
+        },
+    )
+    .unwrap();
+    let pairs_res: PairsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(
+        pairs_res.pairs,
+        vec![pair_infos[1].clone(), pair_infos[2].clone()]
+    );
+    assert_eq!(pairs_res.pools, Some(vec![pool(100u128), pool(200u128)]));
+
+    let query_res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Pairs {
+            start_after: None,
+            limit: None,
+            include_pools: None,
+        },
+    )
+    .unwrap();
+    let pairs_res: PairsResponse = from_binary(&query_res).unwrap();
+    assert_eq!(pairs_res.pools, None);
+}
+
+#[test]
+fn migrate_asset_decimals() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_decimals(&[
+        (&"asset0000".to_string(), &8u8),
+        (&"asset0001".to_string(), &18u8),
+    ]);
+
+    // pairs stored before the decimals registry
+    for (token, pair, liquidity_token) in [
+        ("asset0000", "pair0000", "liquidity0000"),
+        ("asset0001", "pair0001", "liquidity0001"),
+    ]
+    .iter()
+    {
+        let raw_infos = [
+            AssetInfo::Token {
+                contract_addr: token.to_string(),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap(),
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            }
+            .to_raw(deps.as_ref().api)
+            .unwrap(),
+        ];
+        let pair_info_raw = PairInfoRaw {
+            contract_addr: deps.as_ref().api.addr_canonicalize(pair).unwrap(),
+            liquidity_token: deps
+                .as_ref()
+                .api
+                .addr_canonicalize(liquidity_token)
+                .unwrap(),
+            asset_infos: raw_infos.clone(),
+            asset_decimals: [0u8, 0u8],
+        };
+        Map::<&[u8], PairInfoRaw>::new("pair_info")
+            .save(&mut deps.storage, &pair_key(&raw_infos), &pair_info_raw)
+            .unwrap();
+    }
+
+    let query_pairs = |deps: Deps| -> Vec<PairInfo> {
+        let res: PairsResponse = from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::Pairs {
+                    start_after: None,
+                    limit: None,
+                    include_pools: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.pairs
+    };
+    let stored_pairs = query_pairs(deps.as_ref());
+
+    // the decimals of uusd are neither registered nor given, so the pairs are skipped
+    let res = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            native_token_decimals: vec![],
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("migrated_pairs", "2"),
+            attr(
+                "skipped_pairs",
+                format!(
+                    "{},{}",
+                    stored_pairs[0].contract_addr, stored_pairs[1].contract_addr
+                )
+            ),
+            attr("last_pair", stored_pairs[1].contract_addr.as_str()),
+        ]
+    );
+    for pair_info in query_pairs(deps.as_ref()).iter() {
+        assert_eq!(pair_info.asset_decimals, [0u8, 0u8]);
+    }
+
+    // one pair per page
+    let res = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            native_token_decimals: vec![NativeTokenDecimals {
+                denom: "uusd".to_string(),
+                decimals: 6u8,
+            }],
+            start_after: None,
+            limit: Some(1),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("migrated_pairs", "1"),
+            attr("skipped_pairs", ""),
+            attr("last_pair", stored_pairs[0].contract_addr.as_str()),
+        ]
+    );
+    let pairs = query_pairs(deps.as_ref());
+    assert_ne!(pairs[0].asset_decimals, [0u8, 0u8]);
+    assert_eq!(pairs[1].asset_decimals, [0u8, 0u8]);
+
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            native_token_decimals: vec![],
+            start_after: Some(stored_pairs[0].asset_infos.clone()),
+            limit: Some(1),
+        },
+    )
+    .unwrap();
+
+    for pair_info in query_pairs(deps.as_ref()).iter() {
+        let expected_decimals = if pair_info.contract_addr == "pair0000" {
+            [8u8, 6u8]
+        } else {
+            [18u8, 6u8]
+        };
+        assert_eq!(pair_info.asset_decimals, expected_decimals);
+    }
+
+    // the pairs are counted once however often they are migrated
+    let res: PairCountResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PairCount {}).unwrap()).unwrap();
+    assert_eq!(res.count, 2);
+
+    let res: NativeTokenDecimalsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NativeTokenDecimals {
+                denom: "uusd".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.decimals, 6u8);
+}
+
+/// Registers the decimals of asset0000 and uusd
+fn register_decimals(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    deps.querier
+        .with_token_decimals(&[(&"asset0000".to_string(), &6u8)]);
+
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1u128),
+            }],
+        ),
+        ExecuteMsg::AddNativeTokenDecimals {
+            denom: "uusd".to_string(),
+            decimals: 6u8,
+        },
+    )
+    .unwrap();
 }
@@ -5,8 +5,10 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use terraswap::asset::PairInfo;
 use terraswap::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse, QueryMsg,
-    ReverseSimulationResponse, SimulationResponse,
+    Cw20HookMsg, DailyStatsResponse, ExecuteMsg, InstantiateMsg, MaxOfferForImpactResponse,
+    MigrateMsg, PairType, PoolResponse, PriceImpactResponse, QueryMsg, ReverseSimulationResponse,
+    SimulateProvideResponse, SimulateWithdrawResponse, SimulationResponse, SpotPriceResponse,
+    StatsResponse,
 };
 
 fn main() {
@@ -21,7 +23,15 @@ fn main() {
     export_schema(&schema_for!(Cw20HookMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(PairInfo), &out_dir);
+    export_schema(&schema_for!(PairType), &out_dir);
     export_schema(&schema_for!(PoolResponse), &out_dir);
     export_schema(&schema_for!(ReverseSimulationResponse), &out_dir);
     export_schema(&schema_for!(SimulationResponse), &out_dir);
+    export_schema(&schema_for!(StatsResponse), &out_dir);
+    export_schema(&schema_for!(DailyStatsResponse), &out_dir);
+    export_schema(&schema_for!(SpotPriceResponse), &out_dir);
+    export_schema(&schema_for!(PriceImpactResponse), &out_dir);
+    export_schema(&schema_for!(MaxOfferForImpactResponse), &out_dir);
+    export_schema(&schema_for!(SimulateProvideResponse), &out_dir);
+    export_schema(&schema_for!(SimulateWithdrawResponse), &out_dir);
 }
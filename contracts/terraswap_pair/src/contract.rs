@@ -1,13 +1,18 @@
 use crate::error::ContractError;
+use crate::oracle::{compute_oracle_offer_amount, compute_oracle_swap, query_oracle_rate};
 use crate::response::MsgInstantiateContractResponse;
-use crate::state::PAIR_INFO;
+use crate::state::{
+    read_daily_stats, store_swap_stats, OracleConfig, PoolStats, ORACLE_CONFIG, PAIR_INFO,
+    RESERVES, STATS,
+};
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut,
-    Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, Api, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps,
+    DepsMut, Env, MessageInfo, QuerierWrapper, Reply, ReplyOn, Response, StdError, StdResult,
+    Storage, SubMsg, Uint128, WasmMsg,
 };
 
 use cosmwasm_bignumber::{Decimal256, Uint256};
@@ -17,11 +22,13 @@ use protobuf::Message;
 use std::str::FromStr;
 use terraswap::asset::{Asset, AssetInfo, PairInfo, PairInfoRaw};
 use terraswap::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse, QueryMsg,
-    ReverseSimulationResponse, SimulationResponse,
+    AssetStats, Cw20HookMsg, DailyStats, DailyStatsResponse, ExecuteMsg, InstantiateMsg,
+    MaxOfferForImpactResponse, MigrateMsg, PairType, PoolResponse, PriceImpactResponse, QueryMsg,
+    ReverseSimulationResponse, SimulateProvideResponse, SimulateWithdrawResponse,
+    SimulationResponse, SpotPriceResponse, StatsResponse,
 };
-use terraswap::querier::query_supply;
-use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
+use terraswap::querier::{query_supply, query_token_info};
+use terraswap::token::{InstantiateMarketingInfo, InstantiateMsg as TokenInstantiateMsg};
 
 const INSTANTIATE_REPLY_ID: u64 = 1;
 
@@ -41,9 +48,40 @@ pub fn instantiate(
             msg.asset_infos[0].to_raw(deps.api)?,
             msg.asset_infos[1].to_raw(deps.api)?,
         ],
+        asset_decimals: msg.asset_decimals,
     };
 
     PAIR_INFO.save(deps.storage, pair_info)?;
+    RESERVES.save(deps.storage, &[Uint128::zero(), Uint128::zero()])?;
+
+    if let Some(PairType::Oracle {
+        oracle,
+        max_age,
+        spread,
+        skew_fee_rate,
+    }) = msg.pair_type
+    {
+        if spread >= Decimal::one() || skew_fee_rate >= Decimal::one() {
+            return Err(StdError::generic_err(
+                "spread and skew_fee_rate must be less than 1",
+            ));
+        }
+
+        ORACLE_CONFIG.save(
+            deps.storage,
+            &OracleConfig {
+                oracle: deps.api.addr_canonicalize(&oracle)?,
+                max_age,
+                spread,
+                skew_fee_rate,
+            },
+        )?;
+    }
+
+    let symbols = [
+        msg.asset_infos[0].query_symbol(&deps.querier, deps.api)?,
+        msg.asset_infos[1].query_symbol(&deps.querier, deps.api)?,
+    ];
 
     Ok(Response::new().add_submessage(SubMsg {
         // Create LP token
@@ -51,14 +89,23 @@ pub fn instantiate(
             admin: None,
             code_id: msg.token_code_id,
             msg: to_binary(&TokenInstantiateMsg {
-                name: "terraswap liquidity token".to_string(),
-                symbol: "uLP".to_string(),
+                name: lp_token_name(&symbols),
+                symbol: lp_token_symbol(&symbols),
                 decimals: 6,
                 initial_balances: vec![],
                 mint: Some(MinterResponse {
                     minter: env.contract.address.to_string(),
                     cap: None,
                 }),
+                marketing: Some(InstantiateMarketingInfo {
+                    project: Some("Terraswap".to_string()),
+                    description: Some(format!(
+                        "Terraswap liquidity token of the {}-{} pair",
+                        symbols[0], symbols[1]
+                    )),
+                    marketing: None,
+                    logo: None,
+                }),
             })?,
             funds: vec![],
             label: "".to_string(),
@@ -111,6 +158,20 @@ pub fn execute(
                 to_addr,
             )
         }
+        ExecuteMsg::ProvideLiquidityCallback {
+            receiver,
+            slippage_tolerance,
+        } => provide_liquidity_callback(deps, env, info, receiver, slippage_tolerance),
+        ExecuteMsg::Sync {} => sync(deps, env),
+        ExecuteMsg::Skim { to } => {
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+
+            skim(deps, env, info, to_addr)
+        }
     }
 }
 
@@ -131,11 +192,9 @@ pub fn receive_cw20(
             // only asset contract can execute this message
             let mut authorized: bool = false;
             let config: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
-            let pools: [Asset; 2] =
-                config.query_pools(&deps.querier, deps.api, env.contract.address.clone())?;
-            for pool in pools.iter() {
-                if let AssetInfo::Token { contract_addr, .. } = &pool.info {
-                    if contract_addr == &info.sender {
+            for asset_info in config.asset_infos.iter() {
+                if let AssetInfo::Token { contract_addr, .. } = asset_info.to_normal(deps.api)? {
+                    if contract_addr == info.sender.as_str() {
                         authorized = true;
                     }
                 }
@@ -199,6 +258,40 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
     Ok(Response::new().add_attribute("liquidity_token_addr", liquidity_token))
 }
 
+/// LP token name like "ANC-UST LP", cut to the 50 bytes a token name may have
+fn lp_token_name(symbols: &[String; 2]) -> String {
+    let mut name = format!("{}-{} LP", symbols[0], symbols[1]);
+    while name.len() > 50 {
+        name.pop();
+    }
+
+    name
+}
+
+/// LP token symbol like "ANC-UST-LP", only letters and dashes within 12 bytes
+fn lp_token_symbol(symbols: &[String; 2]) -> String {
+    let tickers: Vec<String> = symbols
+        .iter()
+        .map(|symbol| symbol.chars().filter(|c| c.is_ascii_alphabetic()).collect())
+        .collect();
+
+    if tickers.iter().any(|ticker| ticker.is_empty()) {
+        return "uLP".to_string();
+    }
+
+    let symbol = format!("{}-{}-LP", tickers[0], tickers[1]);
+    if symbol.len() <= 12 {
+        return symbol;
+    }
+
+    // 4 + 1 + 4 + 3 bytes
+    format!(
+        "{}-{}-LP",
+        &tickers[0][..tickers[0].len().min(4)],
+        &tickers[1][..tickers[1].len().min(4)]
+    )
+}
+
 /// CONTRACT - should approve contract to use the amount of token
 pub fn provide_liquidity(
     deps: DepsMut,
@@ -213,8 +306,7 @@ pub fn provide_liquidity(
     }
 
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
-    let mut pools: [Asset; 2] =
-        pair_info.query_pools(&deps.querier, deps.api, env.contract.address.clone())?;
+    let pools: [Asset; 2] = load_reserve_pools(deps.storage, deps.api, &pair_info)?;
     let deposits: [Uint128; 2] = [
         assets
             .iter()
@@ -228,8 +320,12 @@ pub fn provide_liquidity(
             .expect("Wrong asset info is given"),
     ];
 
+    // assert slippage tolerance with the given amounts to fail early,
+    // the callback asserts it again with the received amounts
+    assert_slippage_tolerance(&slippage_tolerance, &deposits, &pools)?;
+
     let mut messages: Vec<CosmosMsg> = vec![];
-    for (i, pool) in pools.iter_mut().enumerate() {
+    for (i, pool) in pools.iter().enumerate() {
         // If the pool is token contract, then we need to execute TransferFrom msg to receive funds
         if let AssetInfo::Token { contract_addr, .. } = &pool.info {
             messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -241,48 +337,16 @@ pub fn provide_liquidity(
                 })?,
                 funds: vec![],
             }));
-        } else {
-            // If the asset is native token, balance is already increased
-            // To calculated properly we should subtract user deposit from the pool
-            pool.amount = pool.amount.checked_sub(deposits[i])?;
         }
     }
 
-    // assert slippage tolerance
-    assert_slippage_tolerance(&slippage_tolerance, &deposits, &pools)?;
-
-    let liquidity_token = deps.api.addr_humanize(&pair_info.liquidity_token)?;
-    let total_share = query_supply(&deps.querier, liquidity_token)?;
-    let share = if total_share == Uint128::zero() {
-        // Initial share = collateral amount
-        Uint128::from((deposits[0].u128() * deposits[1].u128()).integer_sqrt())
-    } else {
-        // min(1, 2)
-        // 1. sqrt(deposit_0 * exchange_rate_0_to_1 * deposit_0) * (total_share / sqrt(pool_0 * pool_1))
-        // == deposit_0 * total_share / pool_0
-        // 2. sqrt(deposit_1 * exchange_rate_1_to_0 * deposit_1) * (total_share / sqrt(pool_1 * pool_1))
-        // == deposit_1 * total_share / pool_1
-        std::cmp::min(
-            deposits[0].multiply_ratio(total_share, pools[0].amount),
-            deposits[1].multiply_ratio(total_share, pools[1].amount),
-        )
-    };
-
-    // prevent providing free token
-    if share.is_zero() {
-        return Err(ContractError::InvalidZeroAmount {});
-    }
-
-    // mint LP token to sender
+    // mint LP token after the transfers, with the amounts actually received
     let receiver = receiver.unwrap_or_else(|| info.sender.to_string());
     messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: deps
-            .api
-            .addr_humanize(&pair_info.liquidity_token)?
-            .to_string(),
-        msg: to_binary(&Cw20ExecuteMsg::Mint {
-            recipient: receiver.to_string(),
-            amount: share,
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::ProvideLiquidityCallback {
+            receiver: receiver.clone(),
+            slippage_tolerance,
         })?,
         funds: vec![],
     }));
@@ -292,13 +356,78 @@ pub fn provide_liquidity(
         ("sender", info.sender.as_str()),
         ("receiver", receiver.as_str()),
         ("assets", &format!("{}, {}", assets[0], assets[1])),
-        ("share", &share.to_string()),
     ]))
 }
 
-pub fn withdraw_liquidity(
+/// Mints LP tokens for the balance increase over the reserves
+pub fn provide_liquidity_callback(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
+    receiver: String,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let pools: [Asset; 2] = load_reserve_pools(deps.storage, deps.api, &pair_info)?;
+    let balances: [Asset; 2] =
+        pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
+    let deposits: [Uint128; 2] = [
+        balances[0].amount.checked_sub(pools[0].amount)?,
+        balances[1].amount.checked_sub(pools[1].amount)?,
+    ];
+
+    // assert slippage tolerance
+    assert_slippage_tolerance(&slippage_tolerance, &deposits, &pools)?;
+
+    let liquidity_token = deps.api.addr_humanize(&pair_info.liquidity_token)?;
+    let total_share = query_supply(&deps.querier, liquidity_token.clone())?;
+    let share = compute_share(&deposits, &pools, total_share);
+
+    // prevent providing free token
+    if share.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    RESERVES.save(deps.storage, &[balances[0].amount, balances[1].amount])?;
+
+    // mint LP token to receiver
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: liquidity_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: receiver.clone(),
+                amount: share,
+            })?,
+            funds: vec![],
+        }))
+        .add_attributes(vec![
+            ("action", "provide_liquidity_callback"),
+            ("receiver", receiver.as_str()),
+            (
+                "deposits",
+                &format!(
+                    "{}, {}",
+                    Asset {
+                        info: balances[0].info.clone(),
+                        amount: deposits[0],
+                    },
+                    Asset {
+                        info: balances[1].info.clone(),
+                        amount: deposits[1],
+                    }
+                ),
+            ),
+            ("share", &share.to_string()),
+        ]))
+}
+
+pub fn withdraw_liquidity(
+    deps: DepsMut,
+    _env: Env,
     _info: MessageInfo,
     sender: Addr,
     amount: Uint128,
@@ -306,17 +435,17 @@ pub fn withdraw_liquidity(
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
     let liquidity_addr: Addr = deps.api.addr_humanize(&pair_info.liquidity_token)?;
 
-    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
+    let pools: [Asset; 2] = load_reserve_pools(deps.storage, deps.api, &pair_info)?;
     let total_share: Uint128 = query_supply(&deps.querier, liquidity_addr)?;
 
-    let share_ratio: Decimal = Decimal::from_ratio(amount, total_share);
-    let refund_assets: Vec<Asset> = pools
-        .iter()
-        .map(|a| Asset {
-            info: a.info.clone(),
-            amount: a.amount * share_ratio,
-        })
-        .collect();
+    let refund_assets: [Asset; 2] = compute_refund_assets(&pools, amount, total_share);
+    RESERVES.save(
+        deps.storage,
+        &[
+            pools[0].amount.checked_sub(refund_assets[0].amount)?,
+            pools[1].amount.checked_sub(refund_assets[1].amount)?,
+        ],
+    )?;
 
     // update pool info
     Ok(Response::new()
@@ -348,6 +477,54 @@ pub fn withdraw_liquidity(
         ]))
 }
 
+/// Sets the reserves to the current balances, so donations
+/// and rebases are shared with the liquidity providers
+pub fn sync(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let balances: [Asset; 2] =
+        pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
+
+    RESERVES.save(deps.storage, &[balances[0].amount, balances[1].amount])?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "sync"),
+        ("reserves", &format!("{}, {}", balances[0], balances[1])),
+    ]))
+}
+
+/// Sends the balances exceeding the reserves to the recipient
+pub fn skim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let pools: [Asset; 2] = load_reserve_pools(deps.storage, deps.api, &pair_info)?;
+    let balances: [Asset; 2] =
+        pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
+    let receiver = to.unwrap_or(info.sender);
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut skimmed_assets: Vec<String> = vec![];
+    for (balance, pool) in balances.iter().zip(pools.iter()) {
+        if balance.amount > pool.amount {
+            let excess = Asset {
+                info: balance.info.clone(),
+                amount: balance.amount.checked_sub(pool.amount)?,
+            };
+            skimmed_assets.push(excess.to_string());
+            messages.push(excess.into_msg(&deps.querier, receiver.clone())?);
+        }
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        ("action", "skim"),
+        ("receiver", receiver.as_str()),
+        ("skimmed_assets", &skimmed_assets.join(", ")),
+    ]))
+}
+
 // CONTRACT - a user must do token approval
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
@@ -363,33 +540,38 @@ pub fn swap(
     offer_asset.assert_sent_native_token_balance(&info)?;
 
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let pools: [Asset; 2] = load_reserve_pools(deps.storage, deps.api, &pair_info)?;
 
-    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
-
-    let offer_pool: Asset;
-    let ask_pool: Asset;
-
-    // If the asset balance is already increased
-    // To calculated properly we should subtract user deposit from the pool
-    if offer_asset.info.equal(&pools[0].info) {
-        offer_pool = Asset {
-            amount: pools[0].amount.checked_sub(offer_asset.amount)?,
-            info: pools[0].info.clone(),
-        };
-        ask_pool = pools[1].clone();
+    let offer_index: usize = if offer_asset.info.equal(&pools[0].info) {
+        0
     } else if offer_asset.info.equal(&pools[1].info) {
-        offer_pool = Asset {
-            amount: pools[1].amount.checked_sub(offer_asset.amount)?,
-            info: pools[1].info.clone(),
-        };
-        ask_pool = pools[0].clone();
+        1
     } else {
         return Err(ContractError::AssetMismatch {});
-    }
+    };
+    let offer_pool: Asset = pools[offer_index].clone();
+    let ask_pool: Asset = pools[1 - offer_index].clone();
+
+    // a token may take a fee on transfer, so the offer amount
+    // is what the balance increased over the reserve
+    let offer_amount = if offer_asset.is_native_token() {
+        offer_asset.amount
+    } else {
+        offer_pool
+            .info
+            .query_pool(&deps.querier, deps.api, env.contract.address.clone())?
+            .checked_sub(offer_pool.amount)?
+    };
 
-    let offer_amount = offer_asset.amount;
+    let curve = load_swap_curve(
+        &deps.querier,
+        deps.storage,
+        deps.api,
+        env.block.time.seconds(),
+        &offer_pool.info,
+    )?;
     let (return_amount, spread_amount, commission_amount) =
-        compute_swap(offer_pool.amount, ask_pool.amount, offer_amount);
+        curve.compute_swap(offer_pool.amount, ask_pool.amount, offer_amount)?;
 
     // check max spread limit if exist
     assert_max_spread(
@@ -400,6 +582,20 @@ pub fn swap(
         spread_amount,
     )?;
 
+    let mut reserves: [Uint128; 2] = [pools[0].amount, pools[1].amount];
+    reserves[offer_index] = reserves[offer_index].checked_add(offer_amount)?;
+    reserves[1 - offer_index] = reserves[1 - offer_index].checked_sub(return_amount)?;
+    RESERVES.save(deps.storage, &reserves)?;
+
+    store_swap_stats(
+        deps.storage,
+        env.block.time.seconds(),
+        offer_index,
+        offer_amount,
+        return_amount,
+        commission_amount,
+    )?;
+
     // compute tax
     let return_asset = Asset {
         info: ask_pool.info.clone(),
@@ -431,15 +627,39 @@ pub fn swap(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Pair {} => Ok(to_binary(&query_pair_info(deps)?)?),
+        QueryMsg::PairType {} => Ok(to_binary(&query_pair_type(deps)?)?),
         QueryMsg::Pool {} => Ok(to_binary(&query_pool(deps)?)?),
         QueryMsg::Simulation { offer_asset } => {
-            Ok(to_binary(&query_simulation(deps, offer_asset)?)?)
+            Ok(to_binary(&query_simulation(deps, env, offer_asset)?)?)
         }
         QueryMsg::ReverseSimulation { ask_asset } => {
-            Ok(to_binary(&query_reverse_simulation(deps, ask_asset)?)?)
+            Ok(to_binary(&query_reverse_simulation(deps, env, ask_asset)?)?)
+        }
+        QueryMsg::Stats {} => Ok(to_binary(&query_stats(deps)?)?),
+        QueryMsg::DailyStats { start_after, limit } => {
+            Ok(to_binary(&query_daily_stats(deps, start_after, limit)?)?)
+        }
+        QueryMsg::SpotPrice { base } => Ok(to_binary(&query_spot_price(deps, env, base)?)?),
+        QueryMsg::PriceImpact { offer_asset } => {
+            Ok(to_binary(&query_price_impact(deps, env, offer_asset)?)?)
+        }
+        QueryMsg::MaxOfferForImpact {
+            offer_asset_info,
+            max_impact,
+        } => Ok(to_binary(&query_max_offer_for_impact(
+            deps,
+            env,
+            offer_asset_info,
+            max_impact,
+        )?)?),
+        QueryMsg::SimulateProvide { assets } => {
+            Ok(to_binary(&query_simulate_provide(deps, assets)?)?)
+        }
+        QueryMsg::SimulateWithdraw { amount } => {
+            Ok(to_binary(&query_simulate_withdraw(deps, amount)?)?)
         }
     }
 }
@@ -454,7 +674,8 @@ pub fn query_pair_info(deps: Deps) -> Result<PairInfo, ContractError> {
 pub fn query_pool(deps: Deps) -> Result<PoolResponse, ContractError> {
     let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
     let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
-    let assets: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
+    let assets: [Asset; 2] = load_reserve_pools(deps.storage, deps.api, &pair_info)?;
+    let balances: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
     let total_share: Uint128 = query_supply(
         &deps.querier,
         deps.api.addr_humanize(&pair_info.liquidity_token)?,
@@ -462,35 +683,43 @@ pub fn query_pool(deps: Deps) -> Result<PoolResponse, ContractError> {
 
     let resp = PoolResponse {
         assets,
+        balances,
         total_share,
     };
 
     Ok(resp)
 }
 
+pub fn query_pair_type(deps: Deps) -> Result<PairType, ContractError> {
+    let pair_type = match ORACLE_CONFIG.may_load(deps.storage)? {
+        Some(config) => PairType::Oracle {
+            oracle: deps.api.addr_humanize(&config.oracle)?.to_string(),
+            max_age: config.max_age,
+            spread: config.spread,
+            skew_fee_rate: config.skew_fee_rate,
+        },
+        None => PairType::ConstantProduct {},
+    };
+
+    Ok(pair_type)
+}
+
 pub fn query_simulation(
     deps: Deps,
+    env: Env,
     offer_asset: Asset,
 ) -> Result<SimulationResponse, ContractError> {
-    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
-
-    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
-    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
-
-    let offer_pool: Asset;
-    let ask_pool: Asset;
-    if offer_asset.info.equal(&pools[0].info) {
-        offer_pool = pools[0].clone();
-        ask_pool = pools[1].clone();
-    } else if offer_asset.info.equal(&pools[1].info) {
-        offer_pool = pools[1].clone();
-        ask_pool = pools[0].clone();
-    } else {
-        return Err(ContractError::AssetMismatch {});
-    }
+    let (offer_pool, ask_pool) = query_offer_ask_pools(deps, &offer_asset.info)?;
+    let curve = load_swap_curve(
+        &deps.querier,
+        deps.storage,
+        deps.api,
+        env.block.time.seconds(),
+        &offer_pool.info,
+    )?;
 
     let (return_amount, spread_amount, commission_amount) =
-        compute_swap(offer_pool.amount, ask_pool.amount, offer_asset.amount);
+        curve.compute_swap(offer_pool.amount, ask_pool.amount, offer_asset.amount)?;
 
     Ok(SimulationResponse {
         return_amount,
@@ -501,27 +730,20 @@ pub fn query_simulation(
 
 pub fn query_reverse_simulation(
     deps: Deps,
+    env: Env,
     ask_asset: Asset,
 ) -> Result<ReverseSimulationResponse, ContractError> {
-    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
-
-    let contract_addr = deps.api.addr_humanize(&pair_info.contract_addr)?;
-    let pools: [Asset; 2] = pair_info.query_pools(&deps.querier, deps.api, contract_addr)?;
-
-    let offer_pool: Asset;
-    let ask_pool: Asset;
-    if ask_asset.info.equal(&pools[0].info) {
-        ask_pool = pools[0].clone();
-        offer_pool = pools[1].clone();
-    } else if ask_asset.info.equal(&pools[1].info) {
-        ask_pool = pools[1].clone();
-        offer_pool = pools[0].clone();
-    } else {
-        return Err(ContractError::AssetMismatch {});
-    }
+    let (ask_pool, offer_pool) = query_offer_ask_pools(deps, &ask_asset.info)?;
+    let curve = load_swap_curve(
+        &deps.querier,
+        deps.storage,
+        deps.api,
+        env.block.time.seconds(),
+        &offer_pool.info,
+    )?;
 
     let (offer_amount, spread_amount, commission_amount) =
-        compute_offer_amount(offer_pool.amount, ask_pool.amount, ask_asset.amount)?;
+        curve.compute_offer_amount(offer_pool.amount, ask_pool.amount, ask_asset.amount)?;
 
     Ok(ReverseSimulationResponse {
         offer_amount,
@@ -530,6 +752,290 @@ pub fn query_reverse_simulation(
     })
 }
 
+pub fn query_stats(deps: Deps) -> Result<StatsResponse, ContractError> {
+    let pair_info: PairInfo = PAIR_INFO.load(deps.storage)?.to_normal(deps.api)?;
+    let stats: PoolStats = STATS.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(StatsResponse {
+        assets: to_asset_stats(&pair_info, &stats),
+    })
+}
+
+pub fn query_daily_stats(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<DailyStatsResponse, ContractError> {
+    let pair_info: PairInfo = PAIR_INFO.load(deps.storage)?.to_normal(deps.api)?;
+    let days: Vec<DailyStats> = read_daily_stats(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(day, stats)| DailyStats {
+            day,
+            assets: to_asset_stats(&pair_info, &stats),
+        })
+        .collect();
+
+    Ok(DailyStatsResponse { days })
+}
+
+pub fn query_spot_price(
+    deps: Deps,
+    env: Env,
+    base: AssetInfo,
+) -> Result<SpotPriceResponse, ContractError> {
+    let (base_pool, quote_pool) = query_offer_ask_pools(deps, &base)?;
+    if base_pool.amount.is_zero() || quote_pool.amount.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+
+    let curve = load_swap_curve(
+        &deps.querier,
+        deps.storage,
+        deps.api,
+        env.block.time.seconds(),
+        &base_pool.info,
+    )?;
+    let (quote_amount, base_amount) = curve.spot_price(base_pool.amount, quote_pool.amount);
+
+    Ok(SpotPriceResponse {
+        base: base_pool.info,
+        quote: quote_pool.info,
+        price: Decimal256::from_ratio(quote_amount, base_amount).into(),
+    })
+}
+
+pub fn query_price_impact(
+    deps: Deps,
+    env: Env,
+    offer_asset: Asset,
+) -> Result<PriceImpactResponse, ContractError> {
+    let (offer_pool, ask_pool) = query_offer_ask_pools(deps, &offer_asset.info)?;
+    if offer_pool.amount.is_zero() || ask_pool.amount.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let curve = load_swap_curve(
+        &deps.querier,
+        deps.storage,
+        deps.api,
+        env.block.time.seconds(),
+        &offer_pool.info,
+    )?;
+    let (return_amount, spread_amount, commission_amount) =
+        curve.compute_swap(offer_pool.amount, ask_pool.amount, offer_asset.amount)?;
+    let spot_price = curve.spot_price(offer_pool.amount, ask_pool.amount);
+
+    Ok(PriceImpactResponse {
+        spot_price: Decimal256::from_ratio(spot_price.0, spot_price.1).into(),
+        execution_price: Decimal256::from_ratio(
+            Uint256::from(return_amount),
+            Uint256::from(offer_asset.amount),
+        )
+        .into(),
+        price_impact: compute_price_impact(spot_price, offer_asset.amount, return_amount),
+        return_amount,
+        spread_amount,
+        commission_amount,
+    })
+}
+
+pub fn query_max_offer_for_impact(
+    deps: Deps,
+    env: Env,
+    offer_asset_info: AssetInfo,
+    max_impact: Decimal,
+) -> Result<MaxOfferForImpactResponse, ContractError> {
+    if max_impact >= Decimal::one() {
+        return Err(ContractError::InvalidMaxImpact {});
+    }
+
+    let (offer_pool, ask_pool) = query_offer_ask_pools(deps, &offer_asset_info)?;
+    if offer_pool.amount.is_zero() || ask_pool.amount.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+
+    let curve = load_swap_curve(
+        &deps.querier,
+        deps.storage,
+        deps.api,
+        env.block.time.seconds(),
+        &offer_pool.info,
+    )?;
+    let offer_amount =
+        compute_max_offer_for_impact(&curve, offer_pool.amount, ask_pool.amount, max_impact);
+    if offer_amount.is_zero() {
+        return Ok(MaxOfferForImpactResponse {
+            offer_amount,
+            return_amount: Uint128::zero(),
+            price_impact: Decimal::zero(),
+        });
+    }
+
+    let (return_amount, _, _) =
+        curve.compute_swap(offer_pool.amount, ask_pool.amount, offer_amount)?;
+    Ok(MaxOfferForImpactResponse {
+        offer_amount,
+        return_amount,
+        price_impact: compute_price_impact(
+            curve.spot_price(offer_pool.amount, ask_pool.amount),
+            offer_amount,
+            return_amount,
+        ),
+    })
+}
+
+pub fn query_simulate_provide(
+    deps: Deps,
+    assets: [Asset; 2],
+) -> Result<SimulateProvideResponse, ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let pools: [Asset; 2] = load_reserve_pools(deps.storage, deps.api, &pair_info)?;
+
+    let mut deposits: [Uint128; 2] = [Uint128::zero(), Uint128::zero()];
+    for (i, pool) in pools.iter().enumerate() {
+        deposits[i] = assets
+            .iter()
+            .find(|a| a.info.equal(&pool.info))
+            .map(|a| a.amount)
+            .ok_or(ContractError::AssetMismatch {})?;
+    }
+
+    let total_share = query_supply(
+        &deps.querier,
+        deps.api.addr_humanize(&pair_info.liquidity_token)?,
+    )?;
+    let share = compute_share(&deposits, &pools, total_share);
+    if share.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let used_amounts: [Uint128; 2] = if total_share.is_zero() {
+        deposits
+    } else {
+        let backing_assets = compute_refund_assets(&pools, share, total_share);
+        [
+            std::cmp::min(deposits[0], backing_assets[0].amount),
+            std::cmp::min(deposits[1], backing_assets[1].amount),
+        ]
+    };
+
+    Ok(SimulateProvideResponse {
+        share,
+        used_assets: [
+            Asset {
+                info: pools[0].info.clone(),
+                amount: used_amounts[0],
+            },
+            Asset {
+                info: pools[1].info.clone(),
+                amount: used_amounts[1],
+            },
+        ],
+        unused_assets: [
+            Asset {
+                info: pools[0].info.clone(),
+                amount: deposits[0].checked_sub(used_amounts[0])?,
+            },
+            Asset {
+                info: pools[1].info.clone(),
+                amount: deposits[1].checked_sub(used_amounts[1])?,
+            },
+        ],
+    })
+}
+
+pub fn query_simulate_withdraw(
+    deps: Deps,
+    amount: Uint128,
+) -> Result<SimulateWithdrawResponse, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let pools: [Asset; 2] = load_reserve_pools(deps.storage, deps.api, &pair_info)?;
+    let total_share = query_supply(
+        &deps.querier,
+        deps.api.addr_humanize(&pair_info.liquidity_token)?,
+    )?;
+
+    if amount > total_share {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Withdraw amount exceeds total share",
+        )));
+    }
+
+    let refund_assets: [Asset; 2] = compute_refund_assets(&pools, amount, total_share);
+    let mut received_assets: [Asset; 2] = refund_assets.clone();
+    for asset in received_assets.iter_mut() {
+        asset.amount = asset
+            .amount
+            .checked_sub(asset.compute_tax(&deps.querier)?)?;
+    }
+
+    Ok(SimulateWithdrawResponse {
+        refund_assets,
+        received_assets,
+    })
+}
+
+/// Returns the reserves as the pool assets
+fn load_reserve_pools(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    pair_info: &PairInfoRaw,
+) -> StdResult<[Asset; 2]> {
+    let reserves: [Uint128; 2] = RESERVES.load(storage)?;
+    Ok([
+        Asset {
+            info: pair_info.asset_infos[0].to_normal(api)?,
+            amount: reserves[0],
+        },
+        Asset {
+            info: pair_info.asset_infos[1].to_normal(api)?,
+            amount: reserves[1],
+        },
+    ])
+}
+
+/// Returns the pools of the given offer asset and of the other asset
+fn query_offer_ask_pools(
+    deps: Deps,
+    offer_asset_info: &AssetInfo,
+) -> Result<(Asset, Asset), ContractError> {
+    let pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+    let pools: [Asset; 2] = load_reserve_pools(deps.storage, deps.api, &pair_info)?;
+
+    if offer_asset_info.equal(&pools[0].info) {
+        Ok((pools[0].clone(), pools[1].clone()))
+    } else if offer_asset_info.equal(&pools[1].info) {
+        Ok((pools[1].clone(), pools[0].clone()))
+    } else {
+        Err(ContractError::AssetMismatch {})
+    }
+}
+
+fn to_asset_stats(pair_info: &PairInfo, stats: &PoolStats) -> [AssetStats; 2] {
+    [
+        AssetStats {
+            info: pair_info.asset_infos[0].clone(),
+            volume: stats.volume[0],
+            commission: stats.commission[0],
+            swap_count: stats.swap_count[0],
+        },
+        AssetStats {
+            info: pair_info.asset_infos[1].clone(),
+            volume: stats.volume[1],
+            commission: stats.commission[1],
+            swap_count: stats.swap_count[1],
+        },
+    ]
+}
+
 pub fn amount_of(coins: &[Coin], denom: String) -> Uint128 {
     match coins.iter().find(|x| x.denom == denom) {
         Some(coin) => coin.amount,
@@ -569,6 +1075,242 @@ fn compute_swap(
     )
 }
 
+/// Computes the share minted for the deposits,
+/// an empty pool mints sqrt(deposit_0 * deposit_1)
+fn compute_share(deposits: &[Uint128; 2], pools: &[Asset; 2], total_share: Uint128) -> Uint128 {
+    if total_share == Uint128::zero() {
+        // Initial share = collateral amount
+        Uint128::from((deposits[0].u128() * deposits[1].u128()).integer_sqrt())
+    } else {
+        // min(1, 2)
+        // 1. sqrt(deposit_0 * exchange_rate_0_to_1 * deposit_0) * (total_share / sqrt(pool_0 * pool_1))
+        // == deposit_0 * total_share / pool_0
+        // 2. sqrt(deposit_1 * exchange_rate_1_to_0 * deposit_1) * (total_share / sqrt(pool_1 * pool_1))
+        // == deposit_1 * total_share / pool_1
+        std::cmp::min(
+            deposits[0].multiply_ratio(total_share, pools[0].amount),
+            deposits[1].multiply_ratio(total_share, pools[1].amount),
+        )
+    }
+}
+
+/// Computes the assets redeemed for the share amount
+fn compute_refund_assets(pools: &[Asset; 2], amount: Uint128, total_share: Uint128) -> [Asset; 2] {
+    let share_ratio: Decimal = Decimal::from_ratio(amount, total_share);
+    [
+        Asset {
+            info: pools[0].info.clone(),
+            amount: pools[0].amount * share_ratio,
+        },
+        Asset {
+            info: pools[1].info.clone(),
+            amount: pools[1].amount * share_ratio,
+        },
+    ]
+}
+
+/// Pricing of a swap in a single direction, resolved from the pair type
+enum SwapCurve {
+    ConstantProduct,
+    Oracle {
+        /// Amount of ask asset per unit of offer asset
+        price: Decimal256,
+        spread: Decimal256,
+        skew_fee_rate: Decimal256,
+    },
+}
+
+impl SwapCurve {
+    fn compute_swap(
+        &self,
+        offer_pool: Uint128,
+        ask_pool: Uint128,
+        offer_amount: Uint128,
+    ) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+        match self {
+            SwapCurve::ConstantProduct => Ok(compute_swap(offer_pool, ask_pool, offer_amount)),
+            SwapCurve::Oracle {
+                price,
+                spread,
+                skew_fee_rate,
+            } => {
+                let res = compute_oracle_swap(
+                    offer_pool,
+                    ask_pool,
+                    offer_amount,
+                    *price,
+                    *spread,
+                    *skew_fee_rate,
+                );
+                if res.0 > ask_pool {
+                    return Err(ContractError::InsufficientLiquidity {});
+                }
+
+                Ok(res)
+            }
+        }
+    }
+
+    fn compute_offer_amount(
+        &self,
+        offer_pool: Uint128,
+        ask_pool: Uint128,
+        ask_amount: Uint128,
+    ) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+        match self {
+            SwapCurve::ConstantProduct => compute_offer_amount(offer_pool, ask_pool, ask_amount),
+            SwapCurve::Oracle {
+                price,
+                spread,
+                skew_fee_rate,
+            } => compute_oracle_offer_amount(
+                offer_pool,
+                ask_pool,
+                ask_amount,
+                *price,
+                *spread,
+                *skew_fee_rate,
+            ),
+        }
+    }
+
+    /// Returns the spot price as (ask amount, offer amount)
+    fn spot_price(&self, offer_pool: Uint128, ask_pool: Uint128) -> (Uint256, Uint256) {
+        match self {
+            SwapCurve::ConstantProduct => (ask_pool.into(), offer_pool.into()),
+            SwapCurve::Oracle { price, .. } => (
+                Uint256::from(price.0),
+                Uint256::from(Decimal256::DECIMAL_FRACTIONAL),
+            ),
+        }
+    }
+
+    /// Returns an offer amount at or above the largest one whose
+    /// price impact is at most `max_impact`
+    fn max_offer_upper_bound(
+        &self,
+        offer_pool: Uint128,
+        ask_pool: Uint128,
+        max_impact: Decimal256,
+    ) -> Uint256 {
+        match self {
+            SwapCurve::ConstantProduct => {
+                // Without rounding the impact is
+                // 1 - offer_pool * (1 - commission_rate) / (offer_pool + offer_amount)
+                let commission_rate = Decimal256::from_str(COMMISSION_RATE).unwrap();
+                if max_impact <= commission_rate {
+                    return Uint256::zero();
+                }
+
+                // offer_amount = offer_pool * (max_impact - commission_rate) / (1 - max_impact)
+                Uint256::from(offer_pool)
+                    * ((max_impact - commission_rate) / (Decimal256::one() - max_impact))
+            }
+            SwapCurve::Oracle { price, .. } => {
+                // offer amount worth the whole ask pool
+                Uint256::from(ask_pool).multiply_ratio(Decimal256::DECIMAL_FRACTIONAL, price.0)
+            }
+        }
+    }
+}
+
+/// Resolves the swap pricing for the offer asset, oracle pairs fall back
+/// to constant product while the oracle price is stale
+fn load_swap_curve(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    api: &dyn Api,
+    block_time: u64,
+    offer_asset_info: &AssetInfo,
+) -> Result<SwapCurve, ContractError> {
+    let config = match ORACLE_CONFIG.may_load(storage)? {
+        Some(config) => config,
+        None => return Ok(SwapCurve::ConstantProduct),
+    };
+
+    let pair_info: PairInfoRaw = PAIR_INFO.load(storage)?;
+    let asset_infos: [AssetInfo; 2] = [
+        pair_info.asset_infos[0].to_normal(api)?,
+        pair_info.asset_infos[1].to_normal(api)?,
+    ];
+
+    let rate = match query_oracle_rate(querier, api, &config, &asset_infos, block_time)? {
+        Some(rate) => rate,
+        None => return Ok(SwapCurve::ConstantProduct),
+    };
+
+    let price = if offer_asset_info.equal(&asset_infos[0]) {
+        rate
+    } else {
+        Decimal256::one() / rate
+    };
+
+    if price.is_zero() {
+        return Ok(SwapCurve::ConstantProduct);
+    }
+
+    Ok(SwapCurve::Oracle {
+        price,
+        spread: config.spread.into(),
+        skew_fee_rate: config.skew_fee_rate.into(),
+    })
+}
+
+/// price_impact = 1 - (return_amount / offer_amount) / spot_price
+fn compute_price_impact(
+    spot_price: (Uint256, Uint256),
+    offer_amount: Uint128,
+    return_amount: Uint128,
+) -> Decimal {
+    let offer_amount: Uint256 = offer_amount.into();
+    let return_amount: Uint256 = return_amount.into();
+
+    let price_ratio =
+        Decimal256::from_ratio(return_amount * spot_price.1, offer_amount * spot_price.0);
+    if price_ratio >= Decimal256::one() {
+        return Decimal::zero();
+    }
+
+    (Decimal256::one() - price_ratio).into()
+}
+
+/// Finds the largest offer amount whose price impact is at most `max_impact`,
+/// searching below the upper bound of the curve with the actual swap amounts
+fn compute_max_offer_for_impact(
+    curve: &SwapCurve,
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    max_impact: Decimal,
+) -> Uint128 {
+    let upper_bound = curve.max_offer_upper_bound(offer_pool, ask_pool, max_impact.into());
+    let mut high: u128 = if upper_bound > Uint256::from(u128::MAX - offer_pool.u128()) {
+        u128::MAX - offer_pool.u128()
+    } else {
+        upper_bound.into()
+    };
+
+    let spot_price = curve.spot_price(offer_pool, ask_pool);
+    let mut low: u128 = 0;
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        let offer_amount = Uint128::from(mid);
+        let within_impact = match curve.compute_swap(offer_pool, ask_pool, offer_amount) {
+            Ok((return_amount, _, _)) => {
+                compute_price_impact(spot_price, offer_amount, return_amount) <= max_impact
+            }
+            Err(_) => false,
+        };
+
+        if within_impact {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Uint128::from(low)
+}
+
 #[test]
 fn test_compute_swap_with_huge_pool_variance() {
     let offer_pool = Uint128::from(395451850234u128);
@@ -681,6 +1423,12 @@ fn assert_slippage_tolerance(
         let deposits: [Uint256; 2] = [deposits[0].into(), deposits[1].into()];
         let pools: [Uint256; 2] = [pools[0].amount.into(), pools[1].amount.into()];
 
+        // there is no price to slip from on an empty pool,
+        // and a zero deposit is rejected with a zero share
+        if pools.iter().chain(deposits.iter()).any(|a| a.is_zero()) {
+            return Ok(());
+        }
+
         // Ensure each prices are not dropped as much as slippage tolerance rate
         if Decimal256::from_ratio(deposits[0], deposits[1]) * one_minus_slippage_tolerance
             > Decimal256::from_ratio(pools[0], pools[1])
@@ -695,6 +1443,36 @@ fn assert_slippage_tolerance(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let mut pair_info: PairInfoRaw = PAIR_INFO.load(deps.storage)?;
+
+    // pairs created before reserve accounting start from their balances
+    if RESERVES.may_load(deps.storage)?.is_none() {
+        let balances: [Asset; 2] =
+            pair_info.query_pools(&deps.querier, deps.api, env.contract.address)?;
+        RESERVES.save(deps.storage, &[balances[0].amount, balances[1].amount])?;
+    }
+
+    // pairs created before the decimals registry have no decimals
+    if pair_info.asset_decimals == [0u8, 0u8] {
+        let mut asset_decimals = [0u8; 2];
+        for (decimals, asset_info) in asset_decimals.iter_mut().zip(pair_info.asset_infos.iter()) {
+            *decimals = match asset_info.to_normal(deps.api)? {
+                AssetInfo::Token { contract_addr } => {
+                    query_token_info(&deps.querier, deps.api.addr_validate(&contract_addr)?)?
+                        .decimals
+                }
+                AssetInfo::NativeToken { denom } => msg
+                    .native_token_decimals
+                    .iter()
+                    .find(|native_token| native_token.denom == denom)
+                    .map(|native_token| native_token.decimals)
+                    .ok_or(ContractError::NativeTokenDecimalsNotFound { denom })?,
+            };
+        }
+        pair_info.asset_decimals = asset_decimals;
+        PAIR_INFO.save(deps.storage, &pair_info)?;
+    }
+
     Ok(Response::default())
 }
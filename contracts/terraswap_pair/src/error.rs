@@ -26,4 +26,16 @@ pub enum ContractError {
 
     #[error("Too small offer amount")]
     TooSmallOfferAmount {},
+
+    #[error("Pool is empty")]
+    EmptyPool {},
+
+    #[error("Max impact must be less than 1")]
+    InvalidMaxImpact {},
+
+    #[error("Insufficient liquidity")]
+    InsufficientLiquidity {},
+
+    #[error("Decimals of {denom} are not given")]
+    NativeTokenDecimalsNotFound { denom: String },
 }
@@ -1,4 +1,5 @@
 pub mod contract;
+pub mod oracle;
 pub mod state;
 
 mod error;
@@ -7,6 +7,7 @@ use std::collections::HashMap;
 
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
+use terraswap::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -27,6 +28,7 @@ pub struct WasmMockQuerier {
     base: MockQuerier<TerraQueryWrapper>,
     token_querier: TokenQuerier,
     tax_querier: TaxQuerier,
+    oracle_querier: OracleQuerier,
 }
 
 #[derive(Clone, Default)]
@@ -82,6 +84,12 @@ pub(crate) fn caps_to_map(caps: &[(&String, &Uint128)]) -> HashMap<String, Uint1
     owner_map
 }
 
+#[derive(Clone, Default)]
+pub struct OracleQuerier {
+    // oracle contract address to its price
+    prices: HashMap<String, PriceResponse>,
+}
+
 impl Querier for WasmMockQuerier {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
         // MockQuerier doesn't support Custom, so we ignore it completely here
@@ -126,6 +134,15 @@ impl WasmMockQuerier {
                     panic!("DO NOT ENTER HERE")
                 }
             }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if self.oracle_querier.prices.contains_key(contract_addr) =>
+            {
+                match from_binary(msg).unwrap() {
+                    OracleQueryMsg::Price { .. } => SystemResult::Ok(ContractResult::from(
+                        to_binary(&self.oracle_querier.prices[contract_addr]),
+                    )),
+                }
+            }
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
                 match from_binary(msg).unwrap() {
                     Cw20QueryMsg::TokenInfo {} => {
@@ -204,6 +221,7 @@ impl WasmMockQuerier {
             base,
             token_querier: TokenQuerier::default(),
             tax_querier: TaxQuerier::default(),
+            oracle_querier: OracleQuerier::default(),
         }
     }
 
@@ -217,6 +235,13 @@ impl WasmMockQuerier {
         self.tax_querier = TaxQuerier::new(rate, caps);
     }
 
+    // configure the price returned by the oracle contract
+    pub fn with_oracle_price(&mut self, oracle: &str, rate: Decimal, last_updated: u64) {
+        self.oracle_querier
+            .prices
+            .insert(oracle.to_string(), PriceResponse { rate, last_updated });
+    }
+
     pub fn with_balance(&mut self, balances: &[(&String, Vec<Coin>)]) {
         for (addr, balance) in balances {
             self.base.update_balance(addr.to_string(), balance.clone());
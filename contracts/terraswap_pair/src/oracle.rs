@@ -0,0 +1,147 @@
+use crate::error::ContractError;
+use crate::state::OracleConfig;
+
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{Api, QuerierWrapper, StdResult, Uint128};
+use terraswap::asset::AssetInfo;
+use terraswap::querier::query_oracle_price;
+
+/// Returns the oracle rate (amount of asset 1 per unit of asset 0),
+/// or None when the price is older than `max_age` and the pair
+/// must fall back to constant product pricing
+pub fn query_oracle_rate(
+    querier: &QuerierWrapper,
+    api: &dyn Api,
+    config: &OracleConfig,
+    asset_infos: &[AssetInfo; 2],
+    block_time: u64,
+) -> StdResult<Option<Decimal256>> {
+    let res = query_oracle_price(
+        querier,
+        api.addr_humanize(&config.oracle)?,
+        &asset_infos[0],
+        &asset_infos[1],
+    )?;
+
+    if res.rate.is_zero() || res.last_updated.saturating_add(config.max_age) < block_time {
+        return Ok(None);
+    }
+
+    Ok(Some(res.rate.into()))
+}
+
+/// Swaps at `price` (amount of ask asset per unit of offer asset)
+/// and returns (return_amount, spread_amount, commission_amount).
+/// The spread is a fixed rate of the oracle value, and the commission
+/// is `skew_fee_rate` scaled by how unbalanced the pool is left
+/// towards the offer asset
+pub fn compute_oracle_swap(
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    price: Decimal256,
+    spread: Decimal256,
+    skew_fee_rate: Decimal256,
+) -> (Uint128, Uint128, Uint128) {
+    let offer_pool: Uint256 = offer_pool.into();
+    let ask_pool: Uint256 = ask_pool.into();
+    let offer_amount: Uint256 = offer_amount.into();
+
+    // value of the offer amount in the ask asset
+    let fair_amount: Uint256 = offer_amount * price;
+    let spread_amount: Uint256 = fair_amount * spread;
+
+    // skew = (offer_value - ask_value) / total_value after the swap
+    let total_value: Uint256 = offer_pool * price + ask_pool;
+    let offer_value: Uint256 = offer_pool * price + fair_amount;
+    let ask_value: Uint256 = if fair_amount > ask_pool {
+        Uint256::zero()
+    } else {
+        ask_pool - fair_amount
+    };
+
+    let skew: Decimal256 = if offer_value <= ask_value {
+        Decimal256::zero()
+    } else if total_value.is_zero() {
+        Decimal256::one()
+    } else {
+        std::cmp::min(
+            Decimal256::one(),
+            Decimal256::from_ratio(offer_value - ask_value, total_value),
+        )
+    };
+
+    let commission_amount: Uint256 = (fair_amount - spread_amount) * (skew_fee_rate * skew);
+    let return_amount: Uint256 = fair_amount - spread_amount - commission_amount;
+
+    (
+        return_amount.into(),
+        spread_amount.into(),
+        commission_amount.into(),
+    )
+}
+
+/// Finds the smallest offer amount returning at least `ask_amount`
+/// and returns (offer_amount, spread_amount, commission_amount)
+pub fn compute_oracle_offer_amount(
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    ask_amount: Uint128,
+    price: Decimal256,
+    spread: Decimal256,
+    skew_fee_rate: Decimal256,
+) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+    if ask_amount >= ask_pool {
+        return Err(ContractError::InsufficientLiquidity {});
+    }
+
+    // each unit of offer returns at least price * (1 - spread) * (1 - skew_fee_rate)
+    let min_return_rate: Decimal256 =
+        price * (Decimal256::one() - spread) * (Decimal256::one() - skew_fee_rate);
+    if min_return_rate.is_zero() {
+        return Err(ContractError::TooSmallOfferAmount {});
+    }
+
+    let upper_bound: Uint256 = Uint256::from(ask_amount)
+        .multiply_ratio(Decimal256::DECIMAL_FRACTIONAL, min_return_rate.0)
+        + Uint256::one();
+    let mut high: u128 = if upper_bound > Uint256::from(u128::MAX) {
+        u128::MAX
+    } else {
+        upper_bound.into()
+    };
+
+    let mut low: u128 = 0;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let (return_amount, _, _) = compute_oracle_swap(
+            offer_pool,
+            ask_pool,
+            Uint128::from(mid),
+            price,
+            spread,
+            skew_fee_rate,
+        );
+        if return_amount >= ask_amount {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    let offer_amount = Uint128::from(low);
+    if offer_amount.is_zero() {
+        return Err(ContractError::TooSmallOfferAmount {});
+    }
+
+    let (_, spread_amount, commission_amount) = compute_oracle_swap(
+        offer_pool,
+        ask_pool,
+        offer_amount,
+        price,
+        spread,
+        skew_fee_rate,
+    );
+
+    Ok((offer_amount, spread_amount, commission_amount))
+}
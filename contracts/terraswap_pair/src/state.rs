@@ -1,16 +1,107 @@
-use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map, U64Key};
 use terraswap::asset::PairInfoRaw;
 
 // put the length bytes at the first for compatibility with legacy singleton store
 pub const PAIR_INFO: Item<PairInfoRaw> = Item::new("\u{0}\u{9}pair_info");
 
+/// Pool amounts accounted by the pair in the order of `PairInfoRaw::asset_infos`,
+/// balances above them are unsolicited donations until `Sync` or `Skim`
+pub const RESERVES: Item<[Uint128; 2]> = Item::new("reserves");
+
+/// Pricing parameters of an oracle pair, constant product pairs have none stored
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OracleConfig {
+    pub oracle: CanonicalAddr,
+    pub max_age: u64,
+    pub spread: Decimal,
+    pub skew_fee_rate: Decimal,
+}
+
+pub const ORACLE_CONFIG: Item<OracleConfig> = Item::new("oracle_config");
+
+/// Swap statistics indexed in the same order as `PairInfoRaw::asset_infos`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PoolStats {
+    pub volume: [Uint128; 2],
+    pub commission: [Uint128; 2],
+    pub swap_count: [u64; 2],
+}
+
+impl PoolStats {
+    pub fn record_swap(
+        &mut self,
+        offer_index: usize,
+        offer_amount: Uint128,
+        return_amount: Uint128,
+        commission_amount: Uint128,
+    ) {
+        let ask_index = 1 - offer_index;
+        self.volume[offer_index] += offer_amount;
+        self.volume[ask_index] += return_amount;
+        self.commission[ask_index] += commission_amount;
+        self.swap_count[offer_index] += 1;
+    }
+}
+
+pub const STATS: Item<PoolStats> = Item::new("stats");
+pub const DAILY_STATS: Map<U64Key, PoolStats> = Map::new("daily_stats");
+
+const SECONDS_PER_DAY: u64 = 86400;
+
+pub fn store_swap_stats(
+    storage: &mut dyn Storage,
+    block_time: u64,
+    offer_index: usize,
+    offer_amount: Uint128,
+    return_amount: Uint128,
+    commission_amount: Uint128,
+) -> StdResult<()> {
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    stats.record_swap(offer_index, offer_amount, return_amount, commission_amount);
+    STATS.save(storage, &stats)?;
+
+    let day = U64Key::new(block_time / SECONDS_PER_DAY);
+    let mut daily_stats = DAILY_STATS
+        .may_load(storage, day.clone())?
+        .unwrap_or_default();
+    daily_stats.record_swap(offer_index, offer_amount, return_amount, commission_amount);
+    DAILY_STATS.save(storage, day, &daily_stats)
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_daily_stats(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, PoolStats)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+
+    DAILY_STATS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            let mut day = [0u8; 8];
+            day.copy_from_slice(&k);
+            Ok((u64::from_be_bytes(day), v))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     use cosmwasm_std::testing::mock_dependencies;
     use cosmwasm_std::{Api, StdResult, Storage};
-    use cosmwasm_storage::{singleton, singleton_read};
+    use cosmwasm_storage::{singleton, singleton_read, to_length_prefixed};
     use terraswap::asset::AssetInfoRaw;
     const KEY_PAIR_INFO: &[u8] = b"pair_info";
 
@@ -37,6 +128,7 @@ mod test {
                 ],
                 contract_addr: deps.api.addr_canonicalize("pair0000").unwrap(),
                 liquidity_token: deps.api.addr_canonicalize("liquidity0000").unwrap(),
+                asset_decimals: [6u8, 6u8],
             },
         )
         .unwrap();
@@ -46,4 +138,18 @@ mod test {
             read_pair_info(&deps.storage).unwrap()
         );
     }
+
+    #[test]
+    fn pair_info_without_asset_decimals() {
+        let mut deps = mock_dependencies(&[]);
+        deps.storage.set(
+            &to_length_prefixed(KEY_PAIR_INFO),
+            br#"{"asset_infos":[{"NativeToken":{"denom":"uusd"}},{"NativeToken":{"denom":"uluna"}}],"contract_addr":"cGFpcjAwMDA=","liquidity_token":"bGlxdWlkaXR5MDAwMA=="}"#,
+        );
+
+        assert_eq!(
+            PAIR_INFO.load(&deps.storage).unwrap().asset_decimals,
+            [0u8, 0u8]
+        );
+    }
 }
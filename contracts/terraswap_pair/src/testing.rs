@@ -1,9 +1,12 @@
 use crate::contract::{
-    assert_max_spread, execute, instantiate, query_pair_info, query_pool, query_reverse_simulation,
-    query_simulation, reply,
+    assert_max_spread, execute, instantiate, migrate, query_daily_stats,
+    query_max_offer_for_impact, query_pair_info, query_pair_type, query_pool, query_price_impact,
+    query_reverse_simulation, query_simulate_provide, query_simulate_withdraw, query_simulation,
+    query_spot_price, query_stats, reply,
 };
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
+use crate::state::RESERVES;
 
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
@@ -12,15 +15,20 @@ use cosmwasm_std::{
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
+use terraswap::factory::NativeTokenDecimals;
 use terraswap::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, ReverseSimulationResponse,
-    SimulationResponse,
+    AssetStats, Cw20HookMsg, DailyStatsResponse, ExecuteMsg, InstantiateMsg,
+    MaxOfferForImpactResponse, MigrateMsg, PairType, PoolResponse, PriceImpactResponse,
+    ReverseSimulationResponse, SimulateProvideResponse, SimulateWithdrawResponse,
+    SimulationResponse, SpotPriceResponse, StatsResponse,
 };
-use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
+use terraswap::token::{InstantiateMarketingInfo, InstantiateMsg as TokenInstantiateMsg};
 
 #[test]
 fn proper_initialization() {
     let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_token_balances(&[(&"asset0000".to_string(), &[])]);
 
     let msg = InstantiateMsg {
         asset_infos: [
@@ -32,6 +40,8 @@ fn proper_initialization() {
             },
         ],
         token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
     };
 
     // we can just call .unwrap() to assert this was a success
@@ -44,14 +54,22 @@ fn proper_initialization() {
             msg: WasmMsg::Instantiate {
                 code_id: 10u64,
                 msg: to_binary(&TokenInstantiateMsg {
-                    name: "terraswap liquidity token".to_string(),
-                    symbol: "uLP".to_string(),
+                    name: "UST-mAAPL LP".to_string(),
+                    symbol: "UST-mAAPL-LP".to_string(),
                     decimals: 6,
                     initial_balances: vec![],
                     mint: Some(MinterResponse {
                         minter: MOCK_CONTRACT_ADDR.to_string(),
                         cap: None,
                     }),
+                    marketing: Some(InstantiateMarketingInfo {
+                        project: Some("Terraswap".to_string()),
+                        description: Some(
+                            "Terraswap liquidity token of the UST-mAAPL pair".to_string()
+                        ),
+                        marketing: None,
+                        logo: None,
+                    }),
                 })
                 .unwrap(),
                 funds: vec![],
@@ -122,6 +140,8 @@ fn provide_liquidity() {
             },
         ],
         token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
     };
 
     let env = mock_env();
@@ -175,7 +195,7 @@ fn provide_liquidity() {
     );
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
     let transfer_from_msg = res.messages.get(0).expect("no message");
-    let mint_msg = res.messages.get(1).expect("no message");
+    let callback_msg = res.messages.get(1).expect("no message");
     assert_eq!(
         transfer_from_msg,
         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -190,8 +210,57 @@ fn provide_liquidity() {
         }))
     );
     assert_eq!(
-        mint_msg,
+        callback_msg,
         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&ExecuteMsg::ProvideLiquidityCallback {
+                receiver: "addr0000".to_string(),
+                slippage_tolerance: None,
+            })
+            .unwrap(),
+            funds: vec![],
+        }))
+    );
+
+    // only the pair can execute the callback
+    let msg = ExecuteMsg::ProvideLiquidityCallback {
+        receiver: "addr0000".to_string(),
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    // the callback mints for the received amounts
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+        ),
+    ]);
+
+    let msg = ExecuteMsg::ProvideLiquidityCallback {
+        receiver: "addr0000".to_string(),
+        slippage_tolerance: None,
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "liquidity0000".to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Mint {
                 recipient: "addr0000".to_string(),
@@ -199,7 +268,7 @@ fn provide_liquidity() {
             })
             .unwrap(),
             funds: vec![],
-        }))
+        }))]
     );
 
     // provide more liquidity 1:2, which is not proportional to 1:1,
@@ -208,9 +277,7 @@ fn provide_liquidity() {
         &MOCK_CONTRACT_ADDR.to_string(),
         vec![Coin {
             denom: "uusd".to_string(),
-            amount: Uint128::from(
-                200u128 + 200u128, /* user deposit must be pre-applied */
-            ),
+            amount: Uint128::from(200u128),
         }],
     )]);
 
@@ -225,6 +292,10 @@ fn provide_liquidity() {
         ),
     ]);
 
+    // account the pool balances as reserves
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Sync {}).unwrap();
+
     let msg = ExecuteMsg::ProvideLiquidity {
         assets: [
             Asset {
@@ -253,10 +324,8 @@ fn provide_liquidity() {
         }],
     );
 
-    // only accept 100, then 50 share will be generated with 100 * (100 / 200)
     let res: Response = execute(deps.as_mut(), env, info, msg).unwrap();
     let transfer_from_msg = res.messages.get(0).expect("no message");
-    let mint_msg = res.messages.get(1).expect("no message");
     assert_eq!(
         transfer_from_msg,
         &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -270,9 +339,38 @@ fn provide_liquidity() {
             funds: vec![],
         }))
     );
+
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(200u128 + 200u128),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(200u128 + 100u128),
+            )],
+        ),
+    ]);
+
+    // only accept 100, then 50 share will be generated with 100 * (100 / 200)
+    let msg = ExecuteMsg::ProvideLiquidityCallback {
+        receiver: "staking0000".to_string(),
+        slippage_tolerance: None,
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
     assert_eq!(
-        mint_msg,
-        &SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "liquidity0000".to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Mint {
                 recipient: "staking0000".to_string(), // LP tokens sent to specified receiver
@@ -280,7 +378,7 @@ fn provide_liquidity() {
             })
             .unwrap(),
             funds: vec![],
-        }))
+        }))]
     );
 
     // check wrong argument
@@ -325,9 +423,7 @@ fn provide_liquidity() {
         &MOCK_CONTRACT_ADDR.to_string(),
         vec![Coin {
             denom: "uusd".to_string(),
-            amount: Uint128::from(
-                100u128 + 100u128, /* user deposit must be pre-applied */
-            ),
+            amount: Uint128::from(100u128),
         }],
     )]);
 
@@ -342,6 +438,9 @@ fn provide_liquidity() {
         ),
     ]);
 
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Sync {}).unwrap();
+
     // failed because the price is under slippage_tolerance
     let msg = ExecuteMsg::ProvideLiquidity {
         assets: [
@@ -532,6 +631,8 @@ fn withdraw_liquidity() {
             },
         ],
         token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
     };
 
     let env = mock_env();
@@ -555,6 +656,10 @@ fn withdraw_liquidity() {
 
     let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
 
+    // account the pool balances as reserves
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Sync {}).unwrap();
+
     // withdraw liquidity
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
@@ -653,6 +758,8 @@ fn try_native_to_token() {
             },
         ],
         token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
     };
 
     let env = mock_env();
@@ -676,6 +783,14 @@ fn try_native_to_token() {
 
     let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
 
+    // reserves before the user deposit
+    RESERVES
+        .save(
+            deps.as_mut().storage,
+            &[collateral_pool_amount, asset_pool_amount],
+        )
+        .unwrap();
+
     // normal swap
     let msg = ExecuteMsg::Swap {
         offer_asset: Asset {
@@ -711,17 +826,17 @@ fn try_native_to_token() {
         .unwrap();
     let expected_tax_amount = Uint128::zero(); // no tax for token
 
-    // check simulation res
-    deps.querier.with_balance(&[(
-        &MOCK_CONTRACT_ADDR.to_string(),
-        vec![Coin {
-            denom: "uusd".to_string(),
-            amount: collateral_pool_amount, /* user deposit must be pre-applied */
-        }],
-    )]);
+    // check simulation res with the reserves before the swap
+    RESERVES
+        .save(
+            deps.as_mut().storage,
+            &[collateral_pool_amount, asset_pool_amount],
+        )
+        .unwrap();
 
     let simulation_res: SimulationResponse = query_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
@@ -737,6 +852,7 @@ fn try_native_to_token() {
     // check reverse simulation res
     let reverse_simulation_res: ReverseSimulationResponse = query_reverse_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             info: AssetInfo::Token {
                 contract_addr: "asset0000".to_string(),
@@ -833,6 +949,8 @@ fn try_token_to_native() {
             },
         ],
         token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
     };
 
     let env = mock_env();
@@ -856,6 +974,14 @@ fn try_token_to_native() {
 
     let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
 
+    // reserves before the user deposit
+    RESERVES
+        .save(
+            deps.as_mut().storage,
+            &[collateral_pool_amount, asset_pool_amount],
+        )
+        .unwrap();
+
     // unauthorized access; can not execute swap directly for token swap
     let msg = ExecuteMsg::Swap {
         offer_asset: Asset {
@@ -912,21 +1038,17 @@ fn try_token_to_native() {
             )
             .unwrap(),
     );
-    // check simulation res
-    // return asset token balance as normal
-    deps.querier.with_token_balances(&[
-        (
-            &"liquidity0000".to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &total_share)],
-        ),
-        (
-            &"asset0000".to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &(asset_pool_amount))],
-        ),
-    ]);
+    // check simulation res with the reserves before the swap
+    RESERVES
+        .save(
+            deps.as_mut().storage,
+            &[collateral_pool_amount, asset_pool_amount],
+        )
+        .unwrap();
 
     let simulation_res: SimulationResponse = query_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             amount: offer_amount,
             info: AssetInfo::Token {
@@ -942,6 +1064,7 @@ fn try_token_to_native() {
     // check reverse simulation res
     let reverse_simulation_res: ReverseSimulationResponse = query_reverse_simulation(
         deps.as_ref(),
+        mock_env(),
         Asset {
             amount: expected_return_amount,
             info: AssetInfo::NativeToken {
@@ -1115,6 +1238,8 @@ fn test_query_pool() {
             },
         ],
         token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
     };
 
     let env = mock_env();
@@ -1138,6 +1263,10 @@ fn test_query_pool() {
 
     let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
 
+    // account the pool balances as reserves
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Sync {}).unwrap();
+
     let res: PoolResponse = query_pool(deps.as_ref()).unwrap();
 
     assert_eq!(
@@ -1157,5 +1286,879 @@ fn test_query_pool() {
             }
         ]
     );
+    assert_eq!(res.balances, res.assets);
     assert_eq!(res.total_share, total_share_amount);
+
+    // donations show up in balances but not in reserves
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: asset_0_amount + Uint128::from(100u128),
+        }],
+    )]);
+
+    let res: PoolResponse = query_pool(deps.as_ref()).unwrap();
+    assert_eq!(res.assets[0].amount, asset_0_amount);
+    assert_eq!(
+        res.balances[0].amount,
+        asset_0_amount + Uint128::from(100u128)
+    );
+    assert_eq!(res.balances[1].amount, asset_1_amount);
+}
+
+#[test]
+fn test_stats() {
+    let pool_amount = Uint128::from(1_000_000_000u128);
+    let offer_amount = Uint128::from(1_000_000u128);
+
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: pool_amount + offer_amount, /* user deposit must be pre-applied */
+    }]);
+
+    deps.querier.with_tax(
+        Decimal::zero(),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &pool_amount)],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &pool_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    // we can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // reserves before the user deposit
+    RESERVES
+        .save(deps.as_mut().storage, &[pool_amount, pool_amount])
+        .unwrap();
+
+    // no swap yet
+    let res: StatsResponse = query_stats(deps.as_ref()).unwrap();
+    assert_eq!(res.assets[0].swap_count, 0);
+    assert_eq!(res.assets[1].volume, Uint128::zero());
+
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer_amount,
+        },
+        belief_price: None,
+        max_spread: None,
+        to: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: offer_amount,
+        }],
+    );
+
+    // swap twice on the first day and once on the next day
+    let mut env = mock_env();
+    let first_day = env.block.time.seconds() / 86400;
+    let mut return_amount = Uint128::zero();
+    let mut commission_amount = Uint128::zero();
+    let mut last_commission_amount = Uint128::zero();
+    for i in 0..3 {
+        if i == 2 {
+            env.block.time = env.block.time.plus_seconds(86400);
+        }
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        let attribute = |key: &str| -> Uint128 {
+            let attribute = res.attributes.iter().find(|attr| attr.key == key).unwrap();
+            Uint128::from(attribute.value.parse::<u128>().unwrap())
+        };
+        last_commission_amount = attribute("commission_amount");
+        return_amount += attribute("return_amount");
+        commission_amount += last_commission_amount;
+    }
+
+    let res: StatsResponse = query_stats(deps.as_ref()).unwrap();
+    assert_eq!(
+        res.assets,
+        [
+            AssetStats {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                volume: offer_amount.checked_mul(Uint128::from(3u128)).unwrap(),
+                commission: Uint128::zero(),
+                swap_count: 3,
+            },
+            AssetStats {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                volume: return_amount,
+                commission: commission_amount,
+                swap_count: 0,
+            },
+        ]
+    );
+
+    let res: DailyStatsResponse = query_daily_stats(deps.as_ref(), None, None).unwrap();
+    assert_eq!(res.days.len(), 2);
+    assert_eq!(res.days[0].day, first_day);
+    assert_eq!(res.days[0].assets[0].swap_count, 2);
+    assert_eq!(res.days[1].day, first_day + 1);
+    assert_eq!(res.days[1].assets[0].swap_count, 1);
+    assert_eq!(res.days[1].assets[1].commission, last_commission_amount);
+
+    let res: DailyStatsResponse = query_daily_stats(deps.as_ref(), Some(first_day), None).unwrap();
+    assert_eq!(res.days.len(), 1);
+    assert_eq!(res.days[0].day, first_day + 1);
+}
+
+#[test]
+fn test_price_queries() {
+    let asset_0_amount = Uint128::from(1_000_000_000u128);
+    let asset_1_amount = Uint128::from(2_000_000_000u128);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: asset_0_amount,
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &asset_1_amount)],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // account the pool balances as reserves
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Sync {}).unwrap();
+
+    let native_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let token_info = AssetInfo::Token {
+        contract_addr: "asset0000".to_string(),
+    };
+
+    let res: SpotPriceResponse =
+        query_spot_price(deps.as_ref(), mock_env(), native_info.clone()).unwrap();
+    assert_eq!(
+        res,
+        SpotPriceResponse {
+            base: native_info.clone(),
+            quote: token_info.clone(),
+            price: Decimal::from_ratio(2u128, 1u128),
+        }
+    );
+
+    let res: SpotPriceResponse =
+        query_spot_price(deps.as_ref(), mock_env(), token_info.clone()).unwrap();
+    assert_eq!(res.price, Decimal::from_ratio(1u128, 2u128));
+
+    // offer 1_000_000 uusd
+    // return_amount = 2_000_000_000 - 2 * 10^18 / 1_001_000_000 = 1_998_001
+    // commission_amount = 1_998_001 * 0.003 = 5_994
+    let offer_asset = Asset {
+        info: native_info.clone(),
+        amount: Uint128::from(1_000_000u128),
+    };
+    let res: PriceImpactResponse =
+        query_price_impact(deps.as_ref(), mock_env(), offer_asset.clone()).unwrap();
+    let simulation: SimulationResponse =
+        query_simulation(deps.as_ref(), mock_env(), offer_asset).unwrap();
+    assert_eq!(res.return_amount, simulation.return_amount);
+    assert_eq!(res.spread_amount, simulation.spread_amount);
+    assert_eq!(res.commission_amount, simulation.commission_amount);
+    assert_eq!(
+        res,
+        PriceImpactResponse {
+            spot_price: Decimal::from_ratio(2u128, 1u128),
+            execution_price: Decimal::from_ratio(1_992_007u128, 1_000_000u128),
+            price_impact: Decimal::from_ratio(39_965u128, 10_000_000u128),
+            return_amount: Uint128::from(1_992_007u128),
+            spread_amount: Uint128::from(1_999u128),
+            commission_amount: Uint128::from(5_994u128),
+        }
+    );
+
+    // max_impact below the commission rate cannot be satisfied
+    let res: MaxOfferForImpactResponse = query_max_offer_for_impact(
+        deps.as_ref(),
+        mock_env(),
+        native_info.clone(),
+        Decimal::from_ratio(2u128, 1000u128),
+    )
+    .unwrap();
+    assert_eq!(res.offer_amount, Uint128::zero());
+    assert_eq!(res.return_amount, Uint128::zero());
+
+    // offer_amount <= 1_000_000_000 * (0.01 - 0.003) / (1 - 0.01) = 7_070_707,
+    // rounding down of the return amount lowers it a little more
+    let max_impact = Decimal::percent(1);
+    let res: MaxOfferForImpactResponse =
+        query_max_offer_for_impact(deps.as_ref(), mock_env(), native_info.clone(), max_impact)
+            .unwrap();
+    assert_eq!(res.offer_amount, Uint128::from(7_070_679u128));
+    assert!(res.price_impact <= max_impact);
+
+    let res: PriceImpactResponse = query_price_impact(
+        deps.as_ref(),
+        mock_env(),
+        Asset {
+            info: native_info.clone(),
+            amount: res.offer_amount + Uint128::from(1u128),
+        },
+    )
+    .unwrap();
+    assert!(res.price_impact > max_impact);
+
+    let res = query_max_offer_for_impact(deps.as_ref(), mock_env(), native_info, Decimal::one());
+    assert_eq!(res, Err(ContractError::InvalidMaxImpact {}));
+
+    let res = query_spot_price(
+        deps.as_ref(),
+        mock_env(),
+        AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+    );
+    assert_eq!(res, Err(ContractError::AssetMismatch {}));
+}
+
+#[test]
+fn test_simulate_liquidity() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(200_000u128),
+    }]);
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100_000u128))],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100_000u128))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // account the pool balances as reserves
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Sync {}).unwrap();
+
+    let native_asset = |amount: u128| Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::from(amount),
+    };
+    let token_asset = |amount: u128| Asset {
+        info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        amount: Uint128::from(amount),
+    };
+
+    // share = min(2000 * 100000 / 200000, 2000 * 100000 / 100000) = 1000
+    // 1000 of the token deposit is not backing the share
+    let res: SimulateProvideResponse =
+        query_simulate_provide(deps.as_ref(), [token_asset(2000), native_asset(2000)]).unwrap();
+    assert_eq!(
+        res,
+        SimulateProvideResponse {
+            share: Uint128::from(1000u128),
+            used_assets: [native_asset(2000), token_asset(1000)],
+            unused_assets: [native_asset(0), token_asset(1000)],
+        }
+    );
+
+    let res = query_simulate_provide(
+        deps.as_ref(),
+        [
+            native_asset(2000),
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0001".to_string(),
+                },
+                amount: Uint128::from(2000u128),
+            },
+        ],
+    );
+    assert_eq!(res, Err(ContractError::AssetMismatch {}));
+
+    // 1% of the total share redeems 2000 uusd and 1000 token
+    // tax = 2000 - 2000 / 1.01 = 20
+    let res: SimulateWithdrawResponse =
+        query_simulate_withdraw(deps.as_ref(), Uint128::from(1000u128)).unwrap();
+    assert_eq!(
+        res,
+        SimulateWithdrawResponse {
+            refund_assets: [native_asset(2000), token_asset(1000)],
+            received_assets: [native_asset(1980), token_asset(1000)],
+        }
+    );
+
+    let res = query_simulate_withdraw(deps.as_ref(), Uint128::zero());
+    assert_eq!(res, Err(ContractError::InvalidZeroAmount {}));
+}
+
+#[test]
+fn test_oracle_pair() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1_000_000_000u128),
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(2_000_000_000u128),
+            )],
+        ),
+        (
+            &"liquidity0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128))],
+        ),
+    ]);
+
+    // 2 asset0000 per uusd
+    let now = mock_env().block.time.seconds();
+    deps.querier
+        .with_oracle_price("oracle0000", Decimal::from_ratio(2u128, 1u128), now);
+
+    let pair_type = PairType::Oracle {
+        oracle: "oracle0000".to_string(),
+        max_age: 60,
+        spread: Decimal::permille(1),
+        skew_fee_rate: Decimal::percent(1),
+    };
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        pair_type: Some(PairType::Oracle {
+            oracle: "oracle0000".to_string(),
+            max_age: 60,
+            spread: Decimal::one(),
+            skew_fee_rate: Decimal::percent(1),
+        }),
+        asset_decimals: [6u8, 6u8],
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let res = instantiate(deps.as_mut(), env, info, msg.clone());
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "spread and skew_fee_rate must be less than 1")
+        }
+        _ => panic!("Must return generic error"),
+    }
+
+    let msg = InstantiateMsg {
+        pair_type: Some(pair_type.clone()),
+        ..msg
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // reserves before the user deposit
+    RESERVES
+        .save(
+            deps.as_mut().storage,
+            &[
+                Uint128::from(1_000_000_000u128),
+                Uint128::from(2_000_000_000u128),
+            ],
+        )
+        .unwrap();
+
+    assert_eq!(query_pair_type(deps.as_ref()).unwrap(), pair_type);
+
+    let native_asset = |amount: u128| Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::from(amount),
+    };
+    let token_asset = |amount: u128| Asset {
+        info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        amount: Uint128::from(amount),
+    };
+
+    // offer 1_000_000 uusd worth 2_000_000 asset0000
+    // spread_amount = 2_000_000 * 0.001 = 2_000
+    // skew = (2_002_000_000 - 1_998_000_000) / 4_000_000_000 = 0.001
+    // commission_amount = 1_998_000 * 0.01 * 0.001 = 19
+    let res: SimulationResponse =
+        query_simulation(deps.as_ref(), mock_env(), native_asset(1_000_000)).unwrap();
+    assert_eq!(
+        res,
+        SimulationResponse {
+            return_amount: Uint128::from(1_997_981u128),
+            spread_amount: Uint128::from(2_000u128),
+            commission_amount: Uint128::from(19u128),
+        }
+    );
+
+    let res: ReverseSimulationResponse =
+        query_reverse_simulation(deps.as_ref(), mock_env(), token_asset(1_997_981)).unwrap();
+    assert_eq!(
+        res,
+        ReverseSimulationResponse {
+            offer_amount: Uint128::from(1_000_000u128),
+            spread_amount: Uint128::from(2_000u128),
+            commission_amount: Uint128::from(19u128),
+        }
+    );
+
+    // offer 2_000_000 asset0000 worth 1_000_000 uusd
+    // skew = (1_001_000_000 - 999_000_000) / 2_000_000_000 = 0.001
+    // commission_amount = 999_000 * 0.01 * 0.001 = 9
+    let res: SimulationResponse =
+        query_simulation(deps.as_ref(), mock_env(), token_asset(2_000_000)).unwrap();
+    assert_eq!(res.return_amount, Uint128::from(998_991u128));
+    assert_eq!(res.spread_amount, Uint128::from(1_000u128));
+    assert_eq!(res.commission_amount, Uint128::from(9u128));
+
+    let res: SpotPriceResponse = query_spot_price(
+        deps.as_ref(),
+        mock_env(),
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.price, Decimal::from_ratio(2u128, 1u128));
+
+    let res: PriceImpactResponse =
+        query_price_impact(deps.as_ref(), mock_env(), native_asset(1_000_000)).unwrap();
+    assert_eq!(
+        res.price_impact,
+        Decimal::from_ratio(10_095u128, 10_000_000u128)
+    );
+
+    // swap at the oracle price, the user deposit is already in the pool
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1_001_000_000u128),
+        }],
+    )]);
+
+    let msg = ExecuteMsg::Swap {
+        offer_asset: native_asset(1_000_000),
+        belief_price: None,
+        max_spread: None,
+        to: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1_000_000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(1_997_981u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // stale oracle price falls back to constant product
+    RESERVES
+        .save(
+            deps.as_mut().storage,
+            &[
+                Uint128::from(1_000_000_000u128),
+                Uint128::from(2_000_000_000u128),
+            ],
+        )
+        .unwrap();
+    deps.querier
+        .with_oracle_price("oracle0000", Decimal::from_ratio(2u128, 1u128), now - 61);
+
+    let res: SimulationResponse =
+        query_simulation(deps.as_ref(), mock_env(), native_asset(1_000_000)).unwrap();
+    assert_eq!(res.return_amount, Uint128::from(1_992_007u128));
+}
+
+#[test]
+fn test_balance_delta_accounting() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1_000_000u128),
+    }]);
+
+    deps.querier.with_tax(
+        Decimal::zero(),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(1_000_000u128),
+            )],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(1_000_000u128),
+            )],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [6u8, 6u8],
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Sync {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sync"),
+            attr("reserves", "1000000uusd, 1000000asset0000"),
+        ]
+    );
+
+    // the token takes a 2% fee on transfer, so only 98_000 of 100_000 arrives
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(1_000_000u128),
+            )],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(1_098_000u128),
+            )],
+        ),
+    ]);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100_000u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // 89_253 = 1_000_000 - 1_000_000 * 1_000_000 / 1_098_000
+    // commission_amount = 89_253 * 0.003 = 267
+    assert_eq!(res.attributes[5], attr("offer_amount", "98000"));
+    assert_eq!(res.attributes[6], attr("return_amount", "88986"));
+    assert_eq!(
+        RESERVES.load(deps.as_ref().storage).unwrap(),
+        [Uint128::from(911_014u128), Uint128::from(1_098_000u128)]
+    );
+
+    // someone donates 5_000 asset0000 which anyone can skim
+    deps.querier.with_balance(&[(
+        &MOCK_CONTRACT_ADDR.to_string(),
+        vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(911_014u128),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &"liquidity0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(1_000_000u128),
+            )],
+        ),
+        (
+            &"asset0000".to_string(),
+            &[(
+                &MOCK_CONTRACT_ADDR.to_string(),
+                &Uint128::from(1_103_000u128),
+            )],
+        ),
+    ]);
+
+    let msg = ExecuteMsg::Skim {
+        to: Some("addr0001".to_string()),
+    };
+    let info = mock_info("addr0002", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0001".to_string(),
+                amount: Uint128::from(5_000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+    assert_eq!(
+        RESERVES.load(deps.as_ref().storage).unwrap(),
+        [Uint128::from(911_014u128), Uint128::from(1_098_000u128)]
+    );
+
+    // or sync to the liquidity providers
+    let info = mock_info("addr0002", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Sync {}).unwrap();
+    assert_eq!(
+        RESERVES.load(deps.as_ref().storage).unwrap(),
+        [Uint128::from(911_014u128), Uint128::from(1_103_000u128)]
+    );
+}
+
+#[test]
+fn migrate_asset_decimals() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"asset0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+    )]);
+
+    // a pair created before the decimals registry
+    let msg = InstantiateMsg {
+        asset_infos: [
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+        ],
+        token_code_id: 10u64,
+        pair_type: None,
+        asset_decimals: [0u8, 0u8],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // store liquidity token
+    let reply_msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(
+                vec![
+                    10, 13, 108, 105, 113, 117, 105, 100, 105, 116, 121, 48, 48, 48, 48,
+                ]
+                .into(),
+            ),
+        }),
+    };
+    let _res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+    // the decimals of uusd are not given
+    let res = migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            native_token_decimals: vec![],
+        },
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::NativeTokenDecimalsNotFound {
+            denom: "uusd".to_string()
+        })
+    );
+
+    // the cw20 decimals are queried from the token
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            native_token_decimals: vec![NativeTokenDecimals {
+                denom: "uusd".to_string(),
+                decimals: 8u8,
+            }],
+        },
+    )
+    .unwrap();
+
+    let pair_info: PairInfo = query_pair_info(deps.as_ref()).unwrap();
+    assert_eq!(pair_info.asset_decimals, [8u8, 6u8]);
 }
@@ -2,36 +2,60 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Api, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    QueryRequest, Response, StdError, StdResult, Uint128, WasmMsg, WasmQuery,
+    from_binary, to_binary, Addr, Api, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 
-use crate::operations::execute_swap_operation;
-use crate::querier::compute_tax;
-use crate::state::{Config, CONFIG};
-
-use cw20::Cw20ReceiveMsg;
-use std::collections::HashMap;
-use terra_cosmwasm::{SwapResponse, TerraMsgWrapper, TerraQuerier};
-use terraswap::asset::{Asset, AssetInfo, PairInfo};
-use terraswap::pair::{QueryMsg as PairQueryMsg, SimulationResponse};
-use terraswap::querier::query_pair_info;
+use crate::pool::{
+    execute_update_adapters, execute_update_allowed_pools, load_allowed_pool, query_adapters,
+    query_allowed_pools,
+};
+use crate::route::query_find_best_route;
+use crate::state::{Config, CONFIG, OWNERSHIP_PROPOSAL};
+
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use terra_cosmwasm::TerraMsgWrapper;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::ownership::{
+    claim_ownership, drop_ownership_proposal, propose_new_owner, query_ownership_proposal,
+    renounce_ownership,
+};
 use terraswap::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation,
+    ConfigResponse, Cw20HookMsg, DistributionLeg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    SimulateSplitSwapResponse, SwapInput, SwapOperation,
 };
+use terraswap::routing::{
+    assert_minimum_receive, assert_operations, execute_swap_operation, execute_swap_operations,
+    minimum_receive_msg, optional_addr_validate, reverse_simulate_swap_operations,
+    simulate_swap_operations, swap_operation_msgs, PairContract, RouterExtension,
+};
+
+/// The terraswap router swaps on the pools allowed by its owner and supports no bridges
+pub struct TerraswapRouter;
+
+impl RouterExtension for TerraswapRouter {
+    fn terraswap_factory(&self, deps: Deps) -> StdResult<Addr> {
+        let config: Config = CONFIG.load(deps.storage)?;
+        deps.api.addr_humanize(&config.terraswap_factory)
+    }
+
+    fn pool_pair(&self, deps: Deps, pair_address: &str, protocol: &str) -> StdResult<PairContract> {
+        load_allowed_pool(deps, pair_address, protocol)
+    }
+}
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     CONFIG.save(
         deps.storage,
         &Config {
             terraswap_factory: deps.api.addr_canonicalize(&msg.terraswap_factory)?,
+            owner: Some(deps.api.addr_canonicalize(info.sender.as_str())?),
         },
     )?;
 
@@ -51,6 +75,7 @@ pub fn execute(
             operations,
             minimum_receive,
             to,
+            max_spread,
         } => {
             let api = deps.api;
             execute_swap_operations(
@@ -60,16 +85,117 @@ pub fn execute(
                 operations,
                 minimum_receive,
                 optional_addr_validate(api, to)?,
+                max_spread,
+            )
+        }
+        ExecuteMsg::ExecuteSwapOperationsExactOut {
+            operations,
+            ask_amount,
+            max_offer,
+            to,
+        } => {
+            let offer_asset_info = assert_single_offer(&operations)?;
+            let offer_amount = sent_native_amount(&info, &offer_asset_info)?;
+
+            let api = deps.api;
+            execute_swap_operations_exact_out(
+                deps,
+                env,
+                info.sender,
+                offer_amount,
+                operations,
+                ask_amount,
+                max_offer,
+                optional_addr_validate(api, to)?,
+            )
+        }
+        ExecuteMsg::ExecuteMultiSwap {
+            inputs,
+            minimum_receive,
+            to,
+        } => {
+            let api = deps.api;
+            execute_multi_swap(
+                deps,
+                env,
+                info,
+                inputs,
+                minimum_receive,
+                optional_addr_validate(api, to)?,
+            )
+        }
+        ExecuteMsg::ExecuteDistribution { legs } => {
+            let offer_asset_info = assert_distribution_legs(deps.api, &legs)?;
+            let offer_amount = sent_native_amount(&info, &offer_asset_info)?;
+            execute_distribution(deps, env, info.sender, offer_amount, legs)
+        }
+        ExecuteMsg::ExecuteSplitSwap {
+            routes,
+            minimum_receive,
+            to,
+        } => {
+            let offer_asset_info = assert_split_routes(&routes)?.0;
+            let offer_amount = sent_native_amount(&info, &offer_asset_info)?;
+
+            let api = deps.api;
+            execute_split_swap(
+                deps,
+                env,
+                info.sender,
+                offer_amount,
+                routes,
+                minimum_receive,
+                optional_addr_validate(api, to)?,
             )
         }
-        ExecuteMsg::ExecuteSwapOperation { operation, to } => {
+        ExecuteMsg::UpdateAllowedPools { add, remove } => {
+            execute_update_allowed_pools(deps, env, info, add, remove)
+        }
+        ExecuteMsg::UpdateAdapters { add, remove } => {
+            execute_update_adapters(deps, env, info, add, remove)
+        }
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => Ok(propose_new_owner(
+            deps,
+            env,
+            info,
+            &CONFIG,
+            &OWNERSHIP_PROPOSAL,
+            owner,
+            expires_in,
+        )?),
+        ExecuteMsg::ClaimOwnership {} => Ok(claim_ownership(
+            deps,
+            env,
+            info,
+            &CONFIG,
+            &OWNERSHIP_PROPOSAL,
+        )?),
+        ExecuteMsg::DropOwnershipProposal {} => Ok(drop_ownership_proposal(
+            deps,
+            info,
+            &CONFIG,
+            &OWNERSHIP_PROPOSAL,
+        )?),
+        ExecuteMsg::RenounceOwnership {} => Ok(renounce_ownership(
+            deps,
+            info,
+            &CONFIG,
+            &OWNERSHIP_PROPOSAL,
+        )?),
+        ExecuteMsg::ExecuteSwapOperation {
+            operation,
+            to,
+            offer_amount,
+        } => {
             let api = deps.api;
             execute_swap_operation(
                 deps,
                 env,
                 info,
+                &TerraswapRouter,
                 operation,
                 optional_addr_validate(api, to)?.map(|v| v.to_string()),
+                offer_amount,
             )
         }
         ExecuteMsg::AssertMinimumReceive {
@@ -77,7 +203,7 @@ pub fn execute(
             prev_balance,
             minimum_receive,
             receiver,
-        } => assert_minium_receive(
+        } => assert_minimum_receive(
             deps.as_ref(),
             asset_info,
             prev_balance,
@@ -88,20 +214,39 @@ pub fn execute(
     }
 }
 
-fn optional_addr_validate(api: &dyn Api, addr: Option<String>) -> StdResult<Option<Addr>> {
-    let addr = if let Some(addr) = addr {
-        Some(api.addr_validate(&addr)?)
-    } else {
-        None
-    };
+/// Offer amount sent along with the message, a token offer is only received with the cw20 hook
+fn sent_native_amount(info: &MessageInfo, offer_asset_info: &AssetInfo) -> StdResult<Uint128> {
+    match offer_asset_info {
+        AssetInfo::NativeToken { denom } => Ok(info
+            .funds
+            .iter()
+            .find(|c| &c.denom == denom)
+            .map(|c| c.amount)
+            .unwrap_or_else(Uint128::zero)),
+        AssetInfo::Token { .. } => Err(StdError::generic_err(
+            "token offer must be sent through the token contract",
+        )),
+    }
+}
 
-    Ok(addr)
+fn assert_received_token(info: &MessageInfo, offer_asset_info: &AssetInfo) -> StdResult<()> {
+    if *offer_asset_info
+        != (AssetInfo::Token {
+            contract_addr: info.sender.to_string(),
+        })
+    {
+        return Err(StdError::generic_err(
+            "invalid operations; the received token is not the offer asset",
+        ));
+    }
+
+    Ok(())
 }
 
 pub fn receive_cw20(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> StdResult<Response<TerraMsgWrapper>> {
     let sender = deps.api.addr_validate(&cw20_msg.sender)?;
@@ -110,6 +255,7 @@ pub fn receive_cw20(
             operations,
             minimum_receive,
             to,
+            max_spread,
         } => {
             let api = deps.api;
             execute_swap_operations(
@@ -119,88 +265,451 @@ pub fn receive_cw20(
                 operations,
                 minimum_receive,
                 optional_addr_validate(api, to)?,
+                max_spread,
+            )
+        }
+        Cw20HookMsg::ExecuteSwapOperationsExactOut {
+            operations,
+            ask_amount,
+            max_offer,
+            to,
+        } => {
+            let offer_asset_info = assert_single_offer(&operations)?;
+            assert_received_token(&info, &offer_asset_info)?;
+
+            let api = deps.api;
+            execute_swap_operations_exact_out(
+                deps,
+                env,
+                sender,
+                cw20_msg.amount,
+                operations,
+                ask_amount,
+                max_offer,
+                optional_addr_validate(api, to)?,
+            )
+        }
+        Cw20HookMsg::ExecuteDistribution { legs } => {
+            let offer_asset_info = assert_distribution_legs(deps.api, &legs)?;
+            assert_received_token(&info, &offer_asset_info)?;
+            execute_distribution(deps, env, sender, cw20_msg.amount, legs)
+        }
+        Cw20HookMsg::ExecuteSplitSwap {
+            routes,
+            minimum_receive,
+            to,
+        } => {
+            let offer_asset_info = assert_split_routes(&routes)?.0;
+            assert_received_token(&info, &offer_asset_info)?;
+
+            let api = deps.api;
+            execute_split_swap(
+                deps,
+                env,
+                sender,
+                cw20_msg.amount,
+                routes,
+                minimum_receive,
+                optional_addr_validate(api, to)?,
             )
         }
         _ => Err(StdError::generic_err("Not implemented"))
     }
 }
 
-pub fn execute_swap_operations(
+#[allow(clippy::too_many_arguments)]
+pub fn execute_swap_operations_exact_out(
     deps: DepsMut,
     env: Env,
     sender: Addr,
+    offer_amount: Uint128,
     operations: Vec<SwapOperation>,
-    minimum_receive: Option<Uint128>,
+    ask_amount: Uint128,
+    max_offer: Uint128,
     to: Option<Addr>,
 ) -> StdResult<Response<TerraMsgWrapper>> {
-    let operations_len = operations.len();
-    if operations_len == 0 {
-        return Err(StdError::generic_err("must provide operations"));
+    let offer_asset_info = assert_single_offer(&operations)?;
+    assert_operations(&operations)?;
+
+    let required_amount = reverse_simulate_swap_operations(
+        deps.as_ref(),
+        &TerraswapRouter,
+        ask_amount,
+        operations.clone(),
+    )?
+    .offer_amount;
+    if required_amount > max_offer {
+        return Err(StdError::generic_err(format!(
+            "assertion failed; max offer amount: {}, required offer amount: {}",
+            max_offer, required_amount
+        )));
     }
 
-    // Assert the operations are properly set
-    assert_operations(&operations)?;
+    let refund_amount = offer_amount.checked_sub(required_amount).map_err(|_| {
+        StdError::generic_err(format!(
+            "insufficient offer amount; sent amount: {}, required offer amount: {}",
+            offer_amount, required_amount
+        ))
+    })?;
 
-    let to = if let Some(to) = to { to } else { sender };
-    let target_asset_info = operations.last().unwrap().get_target_asset_info();
-
-    let mut operation_index = 0;
-    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = operations
-        .into_iter()
-        .map(|op| {
-            operation_index += 1;
-            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
-                    operation: op,
-                    to: if operation_index == operations_len {
-                        Some(to.to_string())
-                    } else {
-                        None
-                    },
-                })?,
-            }))
-        })
-        .collect::<StdResult<Vec<CosmosMsg<TerraMsgWrapper>>>>()?;
+    let to = if let Some(to) = to {
+        to
+    } else {
+        sender.clone()
+    };
 
-    // Execute minimum amount assertion
-    if let Some(minimum_receive) = minimum_receive {
-        let receiver_balance = target_asset_info.query_pool(&deps.querier, deps.api, to.clone())?;
+    // The offer not required stays in the router until it is refunded
+    let ask_asset_info = operations.last().unwrap().get_target_asset_info()?;
+    let mut messages = swap_operation_msgs(&env, operations, Some(required_amount), Some(&to))?;
+
+    // The pools can move after the reverse simulation, so the output is asserted
+    messages.push(minimum_receive_msg(
+        deps.as_ref(),
+        &env,
+        ask_asset_info,
+        ask_amount,
+        &to,
+    )?);
+
+    if !refund_amount.is_zero() {
+        messages.push(refund_msg(
+            deps.as_ref(),
+            Asset {
+                info: offer_asset_info,
+                amount: refund_amount,
+            },
+            sender,
+        )?);
+    }
 
-        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: env.contract.address.to_string(),
+    Ok(Response::new().add_messages(messages))
+}
+
+fn refund_msg(deps: Deps, asset: Asset, recipient: Addr) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+    match asset.info.clone() {
+        AssetInfo::NativeToken { .. } => Ok(CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![asset.deduct_tax(&deps.querier)?],
+        })),
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
             funds: vec![],
-            msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
-                asset_info: target_asset_info,
-                prev_balance: receiver_balance,
-                minimum_receive,
-                receiver: to.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: asset.amount,
             })?,
-        }))
+        })),
+    }
+}
+
+pub fn execute_multi_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    inputs: Vec<SwapInput>,
+    minimum_receive: Option<Uint128>,
+    to: Option<Addr>,
+) -> StdResult<Response<TerraMsgWrapper>> {
+    let target_asset_info = assert_swap_inputs(&inputs)?;
+    let to = if let Some(to) = to {
+        to
+    } else {
+        info.sender.clone()
+    };
+
+    // Funds not offered by a native input would be left in the router
+    for coin in info.funds.iter() {
+        let offered = inputs.iter().any(|input| {
+            input.offer_asset.amount == coin.amount
+                && input.offer_asset.info
+                    == AssetInfo::NativeToken {
+                        denom: coin.denom.clone(),
+                    }
+        });
+        if !offered {
+            return Err(StdError::generic_err(format!(
+                "invalid funds; {}{} does not match any native input",
+                coin.amount, coin.denom
+            )));
+        }
+    }
+
+    // Take the tokens before any swap
+    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
+    for input in inputs.iter() {
+        match &input.offer_asset.info {
+            AssetInfo::NativeToken { .. } => {
+                input.offer_asset.assert_sent_native_token_balance(&info)?
+            }
+            AssetInfo::Token { contract_addr } => {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount: input.offer_asset.amount,
+                    })?,
+                }));
+            }
+        }
+    }
+
+    for input in inputs.into_iter() {
+        messages.append(&mut swap_operation_msgs(
+            &env,
+            input.operations,
+            Some(input.offer_asset.amount),
+            Some(&to),
+        )?);
+    }
+
+    // Execute minimum amount assertion over all the inputs
+    if let Some(minimum_receive) = minimum_receive {
+        messages.push(minimum_receive_msg(
+            deps.as_ref(),
+            &env,
+            target_asset_info,
+            minimum_receive,
+            &to,
+        )?);
     }
 
     Ok(Response::new().add_messages(messages))
 }
 
-fn assert_minium_receive(
-    deps: Deps,
-    asset_info: AssetInfo,
-    prev_balance: Uint128,
-    minium_receive: Uint128,
-    receiver: Addr,
+/// Asserts every input swaps its own offer asset into the same ask asset, returning the ask asset
+fn assert_swap_inputs(inputs: &[SwapInput]) -> StdResult<AssetInfo> {
+    if inputs.is_empty() {
+        return Err(StdError::generic_err("must provide inputs"));
+    }
+
+    let offer_asset_infos: Vec<AssetInfo> = inputs
+        .iter()
+        .map(|input| input.offer_asset.info.clone())
+        .collect();
+
+    let mut target_asset_info: Option<AssetInfo> = None;
+    for (index, input) in inputs.iter().enumerate() {
+        if input.offer_asset.amount.is_zero() {
+            return Err(StdError::generic_err("invalid inputs; zero offer amount"));
+        }
+        if offer_asset_infos[..index].contains(&input.offer_asset.info) {
+            return Err(StdError::generic_err(
+                "invalid inputs; duplicate offer asset",
+            ));
+        }
+
+        if assert_single_offer(&input.operations)? != input.offer_asset.info {
+            return Err(StdError::generic_err(
+                "invalid inputs; operations must start with the offer asset",
+            ));
+        }
+        assert_operations(&input.operations)?;
+
+        // The router swaps its whole balance after the first operation,
+        // which would take the offer amount kept for another input
+        for operation in input.operations.iter().skip(1) {
+            if offer_asset_infos.contains(&operation.get_offer_asset_info()?) {
+                return Err(StdError::generic_err(
+                    "invalid inputs; an offer asset is swapped again",
+                ));
+            }
+        }
+
+        let ask_asset_info = input.operations.last().unwrap().get_target_asset_info()?;
+        match &target_asset_info {
+            Some(target_asset_info) => {
+                if *target_asset_info != ask_asset_info {
+                    return Err(StdError::generic_err(
+                        "invalid inputs; inputs must share the ask asset",
+                    ));
+                }
+            }
+            None => target_asset_info = Some(ask_asset_info),
+        }
+    }
+
+    Ok(target_asset_info.unwrap())
+}
+
+pub fn execute_distribution(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    offer_amount: Uint128,
+    legs: Vec<DistributionLeg>,
 ) -> StdResult<Response<TerraMsgWrapper>> {
-    let receiver_balance = asset_info.query_pool(&deps.querier, deps.api, receiver)?;
-    let swap_amount = receiver_balance.checked_sub(prev_balance)?;
+    let offer_asset_info = assert_distribution_legs(deps.api, &legs)?;
+
+    // Each leg is followed by its own assertion, the legs run one after another
+    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
+    let mut spent_amount = Uint128::zero();
+    for leg in legs.into_iter() {
+        let recipient = deps.api.addr_validate(&leg.recipient)?;
+        let ask_asset_info = leg.operations.last().unwrap().get_target_asset_info()?;
+        let leg_offer_amount = reverse_simulate_swap_operations(
+            deps.as_ref(),
+            &TerraswapRouter,
+            leg.minimum_receive,
+            leg.operations.clone(),
+        )?
+        .offer_amount;
+        spent_amount = spent_amount.checked_add(leg_offer_amount)?;
+
+        messages.append(&mut swap_operation_msgs(
+            &env,
+            leg.operations,
+            Some(leg_offer_amount),
+            Some(&recipient),
+        )?);
+        messages.push(minimum_receive_msg(
+            deps.as_ref(),
+            &env,
+            ask_asset_info,
+            leg.minimum_receive,
+            &recipient,
+        )?);
+    }
 
-    if swap_amount < minium_receive {
-        return Err(StdError::generic_err(format!(
-            "assertion failed; minimum receive amount: {}, swap amount: {}",
-            minium_receive, swap_amount
-        )));
+    let refund_amount = offer_amount.checked_sub(spent_amount).map_err(|_| {
+        StdError::generic_err(format!(
+            "insufficient offer amount; sent amount: {}, required offer amount: {}",
+            offer_amount, spent_amount
+        ))
+    })?;
+
+    if !refund_amount.is_zero() {
+        messages.push(refund_msg(
+            deps.as_ref(),
+            Asset {
+                info: offer_asset_info,
+                amount: refund_amount,
+            },
+            sender,
+        )?);
     }
 
-    Ok(Response::default())
+    Ok(Response::new().add_messages(messages))
+}
+
+/// Asserts every leg swaps the same offer asset, returning the offer asset
+fn assert_distribution_legs(api: &dyn Api, legs: &[DistributionLeg]) -> StdResult<AssetInfo> {
+    if legs.is_empty() {
+        return Err(StdError::generic_err("must provide legs"));
+    }
+
+    let mut offer_asset_info: Option<AssetInfo> = None;
+    let mut receivers: Vec<(Addr, AssetInfo)> = vec![];
+    let mut pairs: Vec<String> = vec![];
+    for leg in legs.iter() {
+        if leg.minimum_receive.is_zero() {
+            return Err(StdError::generic_err("invalid legs; zero minimum receive"));
+        }
+
+        let leg_offer_asset_info = assert_single_offer(&leg.operations)?;
+        assert_operations(&leg.operations)?;
+        match &offer_asset_info {
+            Some(offer_asset_info) => {
+                if *offer_asset_info != leg_offer_asset_info {
+                    return Err(StdError::generic_err(
+                        "invalid legs; legs must share the offer asset",
+                    ));
+                }
+            }
+            None => offer_asset_info = Some(leg_offer_asset_info),
+        }
+
+        // The balance of a receiver is queried before any leg runs,
+        // so a second leg to it would count the return of the first
+        let receiver = (
+            api.addr_validate(&leg.recipient)?,
+            leg.operations.last().unwrap().get_target_asset_info()?,
+        );
+        if receivers.contains(&receiver) {
+            return Err(StdError::generic_err(
+                "invalid legs; duplicate recipient and ask asset",
+            ));
+        }
+        receivers.push(receiver);
+
+        // Every leg is reverse simulated before any swap runs, so a pair
+        // swapped by an earlier leg would fail the minimum receive of this one
+        for operation in leg.operations.iter() {
+            let pair = operation_pair(operation)?;
+            if pairs.contains(&pair) {
+                return Err(StdError::generic_err(format!(
+                    "invalid legs; legs must not share a pair: {}",
+                    pair
+                )));
+            }
+        }
+        for operation in leg.operations.iter() {
+            pairs.push(operation_pair(operation)?);
+        }
+    }
+
+    Ok(offer_asset_info.unwrap())
+}
+
+/// Identifies the pair an operation swaps on, independent of the swap direction
+fn operation_pair(operation: &SwapOperation) -> StdResult<String> {
+    if let SwapOperation::Pool { pair_address, .. } = operation {
+        return Ok(pair_address.clone());
+    }
+
+    let mut asset_infos = [
+        operation.get_offer_asset_info()?.to_string(),
+        operation.get_target_asset_info()?.to_string(),
+    ];
+    asset_infos.sort();
+    Ok(asset_infos.join("-"))
+}
+
+pub fn execute_split_swap(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    offer_amount: Uint128,
+    routes: Vec<(Decimal, Vec<SwapOperation>)>,
+    minimum_receive: Option<Uint128>,
+    to: Option<Addr>,
+) -> StdResult<Response<TerraMsgWrapper>> {
+    let target_asset_info = assert_split_routes(&routes)?.1;
+    if offer_amount.is_zero() {
+        return Err(StdError::generic_err("must provide offer amount"));
+    }
+
+    let to = if let Some(to) = to { to } else { sender };
+    let amounts = split_offer_amount(offer_amount, &routes);
+
+    // The routes run one after another
+    let mut messages: Vec<CosmosMsg<TerraMsgWrapper>> = vec![];
+    for ((_, operations), amount) in routes.into_iter().zip(amounts) {
+        if amount.is_zero() {
+            continue;
+        }
+
+        messages.append(&mut swap_operation_msgs(
+            &env,
+            operations,
+            Some(amount),
+            Some(&to),
+        )?);
+    }
+
+    // Execute minimum amount assertion over all the routes
+    if let Some(minimum_receive) = minimum_receive {
+        messages.push(minimum_receive_msg(
+            deps.as_ref(),
+            &env,
+            target_asset_info,
+            minimum_receive,
+            &to,
+        )?);
+    }
+
+    Ok(Response::new().add_messages(messages))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -210,244 +719,187 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::SimulateSwapOperations {
             offer_amount,
             operations,
-        } => to_binary(&simulate_swap_operations(deps, offer_amount, operations)?),
+        } => to_binary(&simulate_swap_operations(
+            deps,
+            &TerraswapRouter,
+            offer_amount,
+            operations,
+        )?),
+        QueryMsg::ReverseSimulateSwapOperations {
+            ask_amount,
+            operations,
+        } => to_binary(&reverse_simulate_swap_operations(
+            deps,
+            &TerraswapRouter,
+            ask_amount,
+            operations,
+        )?),
+        QueryMsg::FindBestRoute {
+            offer_asset,
+            ask_asset_info,
+            max_hops,
+        } => to_binary(&query_find_best_route(
+            deps,
+            offer_asset,
+            ask_asset_info,
+            max_hops,
+        )?),
+        QueryMsg::SimulateSplitSwap {
+            offer_amount,
+            routes,
+        } => to_binary(&simulate_split_swap(deps, offer_amount, routes)?),
+        QueryMsg::AllowedPools { start_after, limit } => {
+            to_binary(&query_allowed_pools(deps, start_after, limit)?)
+        }
+        QueryMsg::Adapters { start_after, limit } => {
+            to_binary(&query_adapters(deps, start_after, limit)?)
+        }
     }
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let owner = deps.api.addr_validate(&msg.owner)?;
+    let owner_raw = deps.api.addr_canonicalize(owner.as_str())?;
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.owner = Some(owner_raw);
+        Ok(config)
+    })?;
+
+    Ok(Response::new().add_attributes(vec![("action", "migrate"), ("owner", owner.as_str())]))
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state = CONFIG.load(deps.storage)?;
+    let ownership_proposal = query_ownership_proposal(deps, &OWNERSHIP_PROPOSAL)?;
+
     let resp = ConfigResponse {
         terraswap_factory: deps
             .api
             .addr_humanize(&state.terraswap_factory)?
             .to_string(),
+        owner: state
+            .owner
+            .map(|owner| deps.api.addr_humanize(&owner).map(|addr| addr.to_string()))
+            .transpose()?,
+        ownership_proposal,
     };
 
     Ok(resp)
 }
 
-fn simulate_swap_operations(
+pub fn simulate_split_swap(
     deps: Deps,
     offer_amount: Uint128,
-    operations: Vec<SwapOperation>,
-) -> StdResult<SimulateSwapOperationsResponse> {
-    let config: Config = CONFIG.load(deps.storage)?;
-    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
-    let terra_querier = TerraQuerier::new(&deps.querier);
+    routes: Vec<(Decimal, Vec<SwapOperation>)>,
+) -> StdResult<SimulateSplitSwapResponse> {
+    assert_split_routes(&routes)?;
+
+    let amounts = split_offer_amount(offer_amount, &routes);
+    let mut return_amounts: Vec<Uint128> = vec![];
+    let mut total_amount = Uint128::zero();
+    for ((_, operations), amount) in routes.into_iter().zip(amounts) {
+        // skipped on execution as well
+        let return_amount = if amount.is_zero() {
+            Uint128::zero()
+        } else {
+            simulate_swap_operations(deps, &TerraswapRouter, amount, operations)?.amount
+        };
 
-    let operations_len = operations.len();
-    if operations_len == 0 {
-        return Err(StdError::generic_err("must provide operations"));
+        total_amount = total_amount.checked_add(return_amount)?;
+        return_amounts.push(return_amount);
     }
 
-    let mut operation_index = 0;
-    let mut offer_amount = offer_amount;
-    for operation in operations.into_iter() {
-        operation_index += 1;
-
-        match operation {
-            SwapOperation::NativeSwap {
-                offer_denom,
-                ask_denom,
-            } => {
-                // Deduct tax before query simulation
-                // because last swap is swap_send
-                if operation_index == operations_len {
-                    offer_amount = offer_amount.checked_sub(compute_tax(
-                        &deps.querier,
-                        offer_amount,
-                        offer_denom.clone(),
-                    )?)?;
-                }
+    Ok(SimulateSplitSwapResponse {
+        amounts: return_amounts,
+        amount: total_amount,
+    })
+}
 
-                let res: SwapResponse = terra_querier.query_swap(
-                    Coin {
-                        denom: offer_denom,
-                        amount: offer_amount,
-                    },
-                    ask_denom,
-                )?;
+/// Divides the offer amount by the route fractions,
+/// the last route takes the remainder of the rounding
+fn split_offer_amount(
+    offer_amount: Uint128,
+    routes: &[(Decimal, Vec<SwapOperation>)],
+) -> Vec<Uint128> {
+    let mut remaining = offer_amount;
+    let mut amounts: Vec<Uint128> = vec![];
+    for (index, (fraction, _)) in routes.iter().enumerate() {
+        let amount = if index + 1 == routes.len() {
+            remaining
+        } else {
+            std::cmp::min(offer_amount * *fraction, remaining)
+        };
 
-                offer_amount = res.receive.amount;
-            }
-            SwapOperation::TerraSwap {
-                offer_asset_info,
-                ask_asset_info,
-            } => {
-                let pair_info: PairInfo = query_pair_info(
-                    &deps.querier,
-                    terraswap_factory.clone(),
-                    &[offer_asset_info.clone(), ask_asset_info.clone()],
-                )?;
-
-                // Deduct tax before querying simulation
-                if let AssetInfo::NativeToken { denom } = offer_asset_info.clone() {
-                    offer_amount = offer_amount.checked_sub(compute_tax(
-                        &deps.querier,
-                        offer_amount,
-                        denom,
-                    )?)?;
-                }
+        remaining -= amount;
+        amounts.push(amount);
+    }
 
-                let mut res: SimulationResponse =
-                    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-                        contract_addr: pair_info.contract_addr.to_string(),
-                        msg: to_binary(&PairQueryMsg::Simulation {
-                            offer_asset: Asset {
-                                info: offer_asset_info,
-                                amount: offer_amount,
-                            },
-                        })?,
-                    }))?;
-
-                // Deduct tax after querying simulation
-                if let AssetInfo::NativeToken { denom } = ask_asset_info {
-                    res.return_amount = res.return_amount.checked_sub(compute_tax(
-                        &deps.querier,
-                        res.return_amount,
-                        denom,
-                    )?)?;
-                }
+    amounts
+}
 
-                offer_amount = res.return_amount;
-            }
-            _ => return Err(StdError::generic_err("not implemented"))
-        }
+/// Asserts the fractions sum up to one and all the routes swap the same
+/// offer asset into the same ask asset, returning both asset infos
+fn assert_split_routes(
+    routes: &[(Decimal, Vec<SwapOperation>)],
+) -> StdResult<(AssetInfo, AssetInfo)> {
+    if routes.is_empty() {
+        return Err(StdError::generic_err("must provide routes"));
     }
 
-    Ok(SimulateSwapOperationsResponse {
-        amount: offer_amount,
-    })
-}
+    let mut fraction_sum = Decimal::zero();
+    let mut route_asset_infos: Option<(AssetInfo, AssetInfo)> = None;
+    for (fraction, operations) in routes.iter() {
+        if fraction.is_zero() {
+            return Err(StdError::generic_err("invalid routes; zero fraction"));
+        }
+        fraction_sum = fraction_sum + *fraction;
 
-fn assert_operations(operations: &[SwapOperation]) -> StdResult<()> {
-    let mut ask_asset_map: HashMap<String, bool> = HashMap::new();
-    for operation in operations.iter() {
-        let (offer_asset, ask_asset) = match operation {
-            SwapOperation::NativeSwap {
-                offer_denom,
-                ask_denom,
-            } => (
-                AssetInfo::NativeToken {
-                    denom: offer_denom.clone(),
-                },
-                AssetInfo::NativeToken {
-                    denom: ask_denom.clone(),
-                },
-            ),
-            SwapOperation::TerraSwap {
-                offer_asset_info,
-                ask_asset_info,
-            } => (offer_asset_info.clone(), ask_asset_info.clone()),
-            _ => return Err(StdError::generic_err("not implemented"))
-        };
+        if operations.is_empty() {
+            return Err(StdError::generic_err("must provide operations"));
+        }
+        assert_operations(operations)?;
+
+        let offer_asset_info = assert_single_offer(operations)?;
+        let ask_asset_info = operations.last().unwrap().get_target_asset_info()?;
 
-        ask_asset_map.remove(&offer_asset.to_string());
-        ask_asset_map.insert(ask_asset.to_string(), true);
+        match &route_asset_infos {
+            Some((offer, ask)) => {
+                if *offer != offer_asset_info || *ask != ask_asset_info {
+                    return Err(StdError::generic_err(
+                        "invalid routes; routes must share the offer and ask asset",
+                    ));
+                }
+            }
+            None => route_asset_infos = Some((offer_asset_info, ask_asset_info)),
+        }
     }
 
-    if ask_asset_map.keys().len() != 1 {
+    if fraction_sum != Decimal::one() {
         return Err(StdError::generic_err(
-            "invalid operations; multiple output token",
+            "invalid routes; fractions must sum up to one",
         ));
     }
 
-    Ok(())
+    Ok(route_asset_infos.unwrap())
 }
 
-#[test]
-fn test_invalid_operations() {
-    // empty error
-    assert!(assert_operations(&[]).is_err());
+/// Returns the offer asset of the first operation, asserting it is not swapped again.
+/// The router swaps its whole balance after the first operation,
+/// which would take the offer amount kept for the next routes or the refund
+fn assert_single_offer(operations: &[SwapOperation]) -> StdResult<AssetInfo> {
+    if operations.is_empty() {
+        return Err(StdError::generic_err("must provide operations"));
+    }
 
-    // uluna output
-    assert!(assert_operations(&vec![
-        SwapOperation::NativeSwap {
-            offer_denom: "uusd".to_string(),
-            ask_denom: "uluna".to_string(),
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-        },
-    ])
-        .is_ok());
-
-    // asset0002 output
-    assert!(assert_operations(&vec![
-        SwapOperation::NativeSwap {
-            offer_denom: "uusd".to_string(),
-            ask_denom: "uluna".to_string(),
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0002".to_string(),
-            },
-        },
-    ])
-        .is_ok());
-
-    // multiple output token types error
-    assert!(assert_operations(&vec![
-        SwapOperation::NativeSwap {
-            offer_denom: "uusd".to_string(),
-            ask_denom: "ukrw".to_string(),
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::Token {
-                contract_addr: "asset0001".to_string(),
-            },
-            ask_asset_info: AssetInfo::NativeToken {
-                denom: "uaud".to_string(),
-            },
-        },
-        SwapOperation::TerraSwap {
-            offer_asset_info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-            ask_asset_info: AssetInfo::Token {
-                contract_addr: "asset0002".to_string(),
-            },
-        },
-    ])
-        .is_err());
+    let offer_asset_info = operations[0].get_offer_asset_info()?;
+    for operation in operations.iter().skip(1) {
+        if operation.get_offer_asset_info()? == offer_asset_info {
+            return Err(StdError::generic_err(
+                "invalid operations; the offer asset is swapped again",
+            ));
+        }
+    }
+
+    Ok(offer_asset_info)
 }
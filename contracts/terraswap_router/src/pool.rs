@@ -0,0 +1,129 @@
+use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+
+use crate::state::{read_adapters, read_allowed_pools, Config, ADAPTERS, ALLOWED_POOLS, CONFIG};
+
+use terra_cosmwasm::TerraMsgWrapper;
+use terraswap::ownership::assert_owner;
+use terraswap::router::{
+    AdaptersResponse, AllowedPool, AllowedPoolsResponse, ProtocolAdapter, TERRASWAP_PROTOCOL,
+};
+use terraswap::routing::PairContract;
+
+/// Pair of a Pool operation, which has to be allowed for the protocol
+pub fn load_allowed_pool(
+    deps: Deps,
+    pair_address: &str,
+    protocol: &str,
+) -> StdResult<PairContract> {
+    let address = deps.api.addr_validate(pair_address)?;
+    let allowed_protocol = ALLOWED_POOLS
+        .may_load(
+            deps.storage,
+            deps.api.addr_canonicalize(address.as_str())?.as_slice(),
+        )?
+        .ok_or_else(|| StdError::generic_err(format!("pool is not allowed: {}", address)))?;
+    if allowed_protocol != protocol {
+        return Err(StdError::generic_err(format!(
+            "pool is allowed for another protocol: {}",
+            allowed_protocol
+        )));
+    }
+
+    if protocol == TERRASWAP_PROTOCOL {
+        return Ok(PairContract::terraswap(address));
+    }
+
+    let adapter = ADAPTERS
+        .may_load(deps.storage, protocol.as_bytes())?
+        .ok_or_else(|| StdError::generic_err(format!("no adapter for {}", protocol)))?;
+    Ok(PairContract {
+        address,
+        adapter: Some(deps.api.addr_humanize(&adapter)?),
+    })
+}
+
+// Only owner can execute it
+pub fn execute_update_allowed_pools(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<AllowedPool>,
+    remove: Vec<String>,
+) -> StdResult<Response<TerraMsgWrapper>> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    assert_owner(deps.as_ref(), &config, &info)?;
+
+    for pool in add.iter() {
+        let pair_address = deps
+            .api
+            .addr_canonicalize(deps.api.addr_validate(&pool.pair_address)?.as_str())?;
+        ALLOWED_POOLS.save(deps.storage, pair_address.as_slice(), &pool.protocol)?;
+    }
+
+    for pair_address in remove.iter() {
+        let pair_address = deps
+            .api
+            .addr_canonicalize(deps.api.addr_validate(pair_address)?.as_str())?;
+        ALLOWED_POOLS.remove(deps.storage, pair_address.as_slice());
+    }
+
+    Ok(Response::new().add_attribute("action", "update_allowed_pools"))
+}
+
+// Only owner can execute it
+pub fn execute_update_adapters(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Vec<ProtocolAdapter>,
+    remove: Vec<String>,
+) -> StdResult<Response<TerraMsgWrapper>> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // permission check
+    assert_owner(deps.as_ref(), &config, &info)?;
+
+    for adapter in add.iter() {
+        if adapter.protocol == TERRASWAP_PROTOCOL {
+            return Err(StdError::generic_err(
+                "the terraswap protocol is swapped without an adapter",
+            ));
+        }
+
+        let adapter_addr = deps
+            .api
+            .addr_canonicalize(deps.api.addr_validate(&adapter.adapter)?.as_str())?;
+        ADAPTERS.save(deps.storage, adapter.protocol.as_bytes(), &adapter_addr)?;
+    }
+
+    for protocol in remove.iter() {
+        ADAPTERS.remove(deps.storage, protocol.as_bytes());
+    }
+
+    Ok(Response::new().add_attribute("action", "update_adapters"))
+}
+
+pub fn query_allowed_pools(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllowedPoolsResponse> {
+    let start_after = match start_after {
+        Some(start_after) => Some(deps.api.addr_canonicalize(&start_after)?),
+        None => None,
+    };
+
+    let pools = read_allowed_pools(deps.storage, deps.api, start_after, limit)?;
+    Ok(AllowedPoolsResponse { pools })
+}
+
+pub fn query_adapters(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AdaptersResponse> {
+    let adapters = read_adapters(deps.storage, deps.api, start_after, limit)?;
+    Ok(AdaptersResponse { adapters })
+}
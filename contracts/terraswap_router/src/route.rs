@@ -0,0 +1,158 @@
+use std::collections::VecDeque;
+
+use cosmwasm_std::{Addr, Deps, StdError, StdResult};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::querier::query_pair_info;
+use terraswap::router::{FindBestRouteResponse, SwapOperation};
+use terraswap::routing::simulate_swap_operations;
+
+use crate::contract::TerraswapRouter;
+use crate::state::{Config, CONFIG};
+
+/// Native tokens a route can go through between the offer and the ask asset
+const ROUTE_DENOMS: [&str; 2] = ["uusd", "uluna"];
+const MAX_HOPS: u32 = 3;
+
+pub fn query_find_best_route(
+    deps: Deps,
+    offer_asset: Asset,
+    ask_asset_info: AssetInfo,
+    max_hops: Option<u32>,
+) -> StdResult<FindBestRouteResponse> {
+    let max_hops = max_hops.unwrap_or(MAX_HOPS).min(MAX_HOPS) as usize;
+    if max_hops == 0 {
+        return Err(StdError::generic_err("max_hops must be positive"));
+    }
+
+    if offer_asset.info == ask_asset_info {
+        return Err(StdError::generic_err("offer and ask assets must differ"));
+    }
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
+
+    // the offer asset comes first and the ask asset last
+    let mut assets: Vec<AssetInfo> = vec![offer_asset.info.clone()];
+    for denom in ROUTE_DENOMS.iter() {
+        let asset_info = AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        };
+        if asset_info != offer_asset.info && asset_info != ask_asset_info {
+            assets.push(asset_info);
+        }
+    }
+    assets.push(ask_asset_info);
+    let ask_index = assets.len() - 1;
+
+    // hops[i][j] holds the operations swapping assets[i] into assets[j]
+    let mut hops: Vec<Vec<Vec<SwapOperation>>> = vec![vec![vec![]; assets.len()]; assets.len()];
+    for (i, offer_asset_info) in assets.iter().enumerate().take(ask_index) {
+        for (j, ask_asset_info) in assets.iter().enumerate().skip(1) {
+            if i != j {
+                hops[i][j] =
+                    hop_operations(deps, &terraswap_factory, offer_asset_info, ask_asset_info);
+            }
+        }
+    }
+
+    // breadth first, so a shorter route wins over a longer one returning the same amount
+    let mut best: Option<FindBestRouteResponse> = None;
+    let mut paths: VecDeque<Vec<usize>> = VecDeque::from(vec![vec![0]]);
+    while let Some(path) = paths.pop_front() {
+        let last = *path.last().unwrap();
+        for next in 1..assets.len() {
+            if path.contains(&next) || hops[last][next].is_empty() {
+                continue;
+            }
+
+            let mut next_path = path.clone();
+            next_path.push(next);
+
+            if next != ask_index {
+                if next_path.len() <= max_hops {
+                    paths.push_back(next_path);
+                }
+                continue;
+            }
+
+            for operations in route_operations(&hops, &next_path) {
+                // a route failing to simulate, e.g. through an empty pool, is skipped
+                let amount = match simulate_swap_operations(
+                    deps,
+                    &TerraswapRouter,
+                    offer_asset.amount,
+                    operations.clone(),
+                ) {
+                    Ok(res) => res.amount,
+                    Err(_) => continue,
+                };
+
+                let better = match &best {
+                    Some(best) => amount > best.amount,
+                    None => true,
+                };
+                if better {
+                    best = Some(FindBestRouteResponse { operations, amount });
+                }
+            }
+        }
+    }
+
+    best.ok_or_else(|| StdError::generic_err("no route found"))
+}
+
+/// Operations swapping the offer asset into the ask asset in a single hop
+fn hop_operations(
+    deps: Deps,
+    terraswap_factory: &Addr,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+) -> Vec<SwapOperation> {
+    let mut operations: Vec<SwapOperation> = vec![];
+    if query_pair_info(
+        &deps.querier,
+        terraswap_factory.clone(),
+        &[offer_asset_info.clone(), ask_asset_info.clone()],
+    )
+    .is_ok()
+    {
+        operations.push(SwapOperation::TerraSwap {
+            offer_asset_info: offer_asset_info.clone(),
+            ask_asset_info: ask_asset_info.clone(),
+            max_spread: None,
+            belief_price: None,
+        });
+    }
+
+    if let (
+        AssetInfo::NativeToken { denom: offer_denom },
+        AssetInfo::NativeToken { denom: ask_denom },
+    ) = (offer_asset_info, ask_asset_info)
+    {
+        operations.push(SwapOperation::NativeSwap {
+            offer_denom: offer_denom.clone(),
+            ask_denom: ask_denom.clone(),
+        });
+    }
+
+    operations
+}
+
+/// Every combination of the hop operations along the path
+fn route_operations(hops: &[Vec<Vec<SwapOperation>>], path: &[usize]) -> Vec<Vec<SwapOperation>> {
+    let mut routes: Vec<Vec<SwapOperation>> = vec![vec![]];
+    for step in path.windows(2) {
+        routes = routes
+            .into_iter()
+            .flat_map(|route| {
+                hops[step[0]][step[1]].iter().map(move |operation| {
+                    let mut route = route.clone();
+                    route.push(operation.clone());
+                    route
+                })
+            })
+            .collect();
+    }
+
+    routes
+}
@@ -1,17 +1,86 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::CanonicalAddr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Api, CanonicalAddr, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Item, Map};
+use terraswap::ownership::{Owned, OwnershipProposal};
+use terraswap::router::{AllowedPool, ProtocolAdapter};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub terraswap_factory: CanonicalAddr,
+    /// None once the ownership is renounced, and in the configs stored before the owner
+    #[serde(default)]
+    pub owner: Option<CanonicalAddr>,
 }
 
 // put the length bytes at the first for compatibility with legacy singleton store
 pub const CONFIG: Item<Config> = Item::new("\u{0}\u{6}config");
 
+impl Owned for Config {
+    fn owner(&self) -> Option<&CanonicalAddr> {
+        self.owner.as_ref()
+    }
+
+    fn set_owner(&mut self, owner: Option<CanonicalAddr>) {
+        self.owner = owner;
+    }
+}
+
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// Protocol of the pools Pool operations can swap on, keyed by the pair contract address
+pub const ALLOWED_POOLS: Map<&[u8], String> = Map::new("allowed_pools");
+/// Adapter contracts keyed by their protocol
+pub const ADAPTERS: Map<&[u8], CanonicalAddr> = Map::new("adapters");
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+pub fn read_allowed_pools(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AllowedPool>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|addr| Bound::exclusive(addr.as_slice()));
+
+    ALLOWED_POOLS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, protocol) = item?;
+            Ok(AllowedPool {
+                pair_address: api.addr_humanize(&CanonicalAddr::from(key))?.to_string(),
+                protocol,
+            })
+        })
+        .collect()
+}
+
+pub fn read_adapters(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ProtocolAdapter>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|protocol| Bound::exclusive(protocol.as_bytes()));
+
+    ADAPTERS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, adapter) = item?;
+            Ok(ProtocolAdapter {
+                protocol: String::from_utf8(key)?,
+                adapter: api.addr_humanize(&adapter)?.to_string(),
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -35,6 +104,7 @@ mod test {
             &mut deps.storage,
             &Config {
                 terraswap_factory: deps.api.addr_canonicalize("addr0000").unwrap(),
+                owner: None,
             },
         )
         .unwrap();
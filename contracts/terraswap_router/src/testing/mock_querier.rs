@@ -1,7 +1,7 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
-    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, Fraction, OwnedDeps,
+    Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -9,16 +9,27 @@ use std::collections::HashMap;
 
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
 use terra_cosmwasm::{
-    SwapResponse, TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute,
+    ExchangeRateItem, ExchangeRatesResponse, SwapResponse, TaxCapResponse, TaxRateResponse,
+    TerraQuery, TerraQueryWrapper, TerraRoute,
 };
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
-use terraswap::pair::SimulationResponse;
+use terraswap::pair::{ReverseSimulationResponse, SimulationResponse, SpotPriceResponse};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Pair { asset_infos: [AssetInfo; 2] },
     Simulation { offer_asset: Asset },
+    ReverseSimulation { ask_asset: Asset },
+    SpotPrice { base: AssetInfo },
+    Pool {},
+}
+
+/// Pool response of the pairs deployed before the balances were added to it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyPoolResponse {
+    pub assets: [Asset; 2],
+    pub total_share: Uint128,
 }
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
@@ -99,12 +110,18 @@ pub(crate) fn caps_to_map(caps: &[(&String, &Uint128)]) -> HashMap<String, Uint1
 #[derive(Clone, Default)]
 pub struct TerraswapFactoryQuerier {
     pairs: HashMap<String, String>,
+    // simulation return rates by the pair contract, 1 if not set
+    rates: HashMap<String, Decimal>,
+    // reserves of the pairs deployed before the spot price query
+    legacy_pools: HashMap<String, [Asset; 2]>,
 }
 
 impl TerraswapFactoryQuerier {
     pub fn new(pairs: &[(&String, &String)]) -> Self {
         TerraswapFactoryQuerier {
             pairs: pairs_to_map(pairs),
+            rates: HashMap::new(),
+            legacy_pools: HashMap::new(),
         }
     }
 }
@@ -176,6 +193,27 @@ impl WasmMockQuerier {
                         }
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if route == &TerraRoute::Oracle {
+                    match query_data {
+                        TerraQuery::ExchangeRates {
+                            base_denom,
+                            quote_denoms,
+                        } => {
+                            // the market swaps one to one
+                            let res = ExchangeRatesResponse {
+                                base_denom: base_denom.to_string(),
+                                exchange_rates: quote_denoms
+                                    .iter()
+                                    .map(|quote_denom| ExchangeRateItem {
+                                        quote_denom: quote_denom.to_string(),
+                                        exchange_rate: Decimal::one(),
+                                    })
+                                    .collect(),
+                            };
+                            SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                        }
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
                 } else {
                     panic!("DO NOT ENTER HERE")
                 }
@@ -195,6 +233,7 @@ impl WasmMockQuerier {
                                     denom: "uusd".to_string(),
                                 },
                             ],
+                            asset_decimals: [6u8, 6u8],
                         }))),
                         None => SystemResult::Err(SystemError::InvalidRequest {
                             error: "No pair info exists".to_string(),
@@ -203,12 +242,65 @@ impl WasmMockQuerier {
                     }
                 }
                 Ok(QueryMsg::Simulation { offer_asset }) => {
+                    let rate = match self.terraswap_factory_querier.rates.get(contract_addr) {
+                        Some(rate) => *rate,
+                        None => Decimal::one(),
+                    };
+
                     SystemResult::Ok(ContractResult::from(to_binary(&SimulationResponse {
-                        return_amount: offer_asset.amount,
+                        return_amount: offer_asset.amount * rate,
                         commission_amount: Uint128::zero(),
                         spread_amount: Uint128::zero(),
                     })))
                 }
+                Ok(QueryMsg::SpotPrice { .. })
+                    if self
+                        .terraswap_factory_querier
+                        .legacy_pools
+                        .contains_key(contract_addr) =>
+                {
+                    SystemResult::Ok(ContractResult::Err(
+                        "Error parsing into type terraswap::pair::QueryMsg: unknown variant `spot_price`"
+                            .to_string(),
+                    ))
+                }
+                Ok(QueryMsg::SpotPrice { base }) => {
+                    let rate = match self.terraswap_factory_querier.rates.get(contract_addr) {
+                        Some(rate) => *rate,
+                        None => Decimal::one(),
+                    };
+
+                    SystemResult::Ok(ContractResult::from(to_binary(&SpotPriceResponse {
+                        base: base.clone(),
+                        quote: base,
+                        price: rate,
+                    })))
+                }
+                Ok(QueryMsg::Pool {}) => {
+                    match self.terraswap_factory_querier.legacy_pools.get(contract_addr) {
+                        Some(assets) => {
+                            SystemResult::Ok(ContractResult::from(to_binary(&LegacyPoolResponse {
+                                assets: assets.clone(),
+                                total_share: Uint128::zero(),
+                            })))
+                        }
+                        None => panic!("DO NOT ENTER HERE"),
+                    }
+                }
+                Ok(QueryMsg::ReverseSimulation { ask_asset }) => {
+                    let rate = match self.terraswap_factory_querier.rates.get(contract_addr) {
+                        Some(rate) => *rate,
+                        None => Decimal::one(),
+                    };
+
+                    SystemResult::Ok(ContractResult::from(to_binary(
+                        &ReverseSimulationResponse {
+                            offer_amount: ask_asset.amount * rate.inv().unwrap(),
+                            commission_amount: Uint128::zero(),
+                            spread_amount: Uint128::zero(),
+                        },
+                    )))
+                }
                 _ => match from_binary(msg).unwrap() {
                     Cw20QueryMsg::Balance { address } => {
                         let balances: &HashMap<String, Uint128> =
@@ -276,4 +368,20 @@ impl WasmMockQuerier {
     pub fn with_terraswap_pairs(&mut self, pairs: &[(&String, &String)]) {
         self.terraswap_factory_querier = TerraswapFactoryQuerier::new(pairs);
     }
+
+    pub fn with_legacy_pools(&mut self, pools: &[(&String, &[Asset; 2])]) {
+        for (pair, assets) in pools {
+            self.terraswap_factory_querier
+                .legacy_pools
+                .insert(pair.to_string(), (*assets).clone());
+        }
+    }
+
+    pub fn with_pair_rates(&mut self, rates: &[(&String, &Decimal)]) {
+        for (pair, rate) in rates {
+            self.terraswap_factory_querier
+                .rates
+                .insert(pair.to_string(), **rate);
+        }
+    }
 }
@@ -1,18 +1,23 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, to_binary, Coin, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
+    from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
 };
 
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::state::{Config, CONFIG};
 use crate::testing::mock_querier::mock_dependencies;
 
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use terra_cosmwasm::{create_swap_msg, create_swap_send_msg};
 use terraswap::asset::{Asset, AssetInfo};
+use terraswap::ownership::OwnershipProposalResponse;
 use terraswap::pair::ExecuteMsg as PairExecuteMsg;
 use terraswap::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation,
+    AdapterCw20HookMsg, AdapterExecuteMsg, AdaptersResponse, AllowedPool, AllowedPoolsResponse,
+    ConfigResponse, Cw20HookMsg, DistributionLeg, ExecuteMsg, FindBestRouteResponse,
+    InstantiateMsg, MigrateMsg, ProtocolAdapter, QueryMsg, ReverseSimulateSwapOperationsResponse,
+    SimulateSplitSwapResponse, SimulateSwapOperationsResponse, SwapInput, SwapOperation,
+    SwapOperationSimulation,
 };
 
 #[test]
@@ -32,6 +37,129 @@ fn proper_initialization() {
     let config: ConfigResponse =
         from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
     assert_eq!("terraswapfactory", config.terraswap_factory.as_str());
+    assert_eq!(Some("addr0000".to_string()), config.owner);
+    assert_eq!(None, config.ownership_proposal);
+}
+
+#[test]
+fn ownership_transfer() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only the owner can propose
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: "addr0001".to_string(),
+        expires_in: 100,
+    };
+    let info = mock_info("addr0001", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        Some(OwnershipProposalResponse {
+            owner: "addr0001".to_string(),
+            expires_at: mock_env().block.time.seconds() + 100,
+        }),
+        config.ownership_proposal
+    );
+
+    // only the proposed owner can claim
+    let info = mock_info("addr0002", &[]);
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ClaimOwnership {},
+    ) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("addr0001", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ClaimOwnership {},
+    )
+    .unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(Some("addr0001".to_string()), config.owner);
+    assert_eq!(None, config.ownership_proposal);
+
+    // nobody can update the allowed pools once the ownership is renounced
+    let info = mock_info("addr0001", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::RenounceOwnership {},
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::UpdateAllowedPools {
+        add: vec![AllowedPool {
+            pair_address: "pool0000".to_string(),
+            protocol: "terraswap".to_string(),
+        }],
+        remove: vec![],
+    };
+    let info = mock_info("addr0001", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn migrate_owner() {
+    let mut deps = mock_dependencies(&[]);
+
+    // routers stored before the owner have none
+    let config = Config {
+        terraswap_factory: deps
+            .as_ref()
+            .api
+            .addr_canonicalize("terraswapfactory")
+            .unwrap(),
+        owner: None,
+    };
+    CONFIG.save(&mut deps.storage, &config).unwrap();
+
+    let msg = MigrateMsg {
+        owner: "addr0000".to_string(),
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!("terraswapfactory", config.terraswap_factory.as_str());
+    assert_eq!(Some("addr0000".to_string()), config.owner);
+
+    // the owner can update the allowed pools
+    let msg = ExecuteMsg::UpdateAllowedPools {
+        add: vec![AllowedPool {
+            pair_address: "pool0000".to_string(),
+            protocol: "terraswap".to_string(),
+        }],
+        remove: vec![],
+    };
+    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 }
 
 #[test]
@@ -55,6 +183,7 @@ fn execute_swap_operations() {
         operations: vec![],
         minimum_receive: None,
         to: None,
+        max_spread: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -77,6 +206,8 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: "asset0001".to_string(),
                 },
+                max_spread: None,
+                belief_price: None,
             },
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::Token {
@@ -85,6 +216,8 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: "uluna".to_string(),
                 },
+                max_spread: None,
+                belief_price: None,
             },
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::NativeToken {
@@ -93,10 +226,13 @@ fn execute_swap_operations() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: "asset0002".to_string(),
                 },
+                max_spread: None,
+                belief_price: None,
             },
         ],
         minimum_receive: Some(Uint128::from(1000000u128)),
         to: None,
+        max_spread: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -113,6 +249,7 @@ fn execute_swap_operations() {
                         ask_denom: "ukrw".to_string(),
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -127,8 +264,11 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0001".to_string(),
                         },
+                        max_spread: None,
+                        belief_price: None,
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -143,8 +283,11 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
+                        max_spread: None,
+                        belief_price: None,
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -159,8 +302,11 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0002".to_string(),
                         },
+                        max_spread: None,
+                        belief_price: None,
                     },
                     to: Some("addr0000".to_string()),
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -196,6 +342,8 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: "asset0001".to_string(),
                     },
+                    max_spread: None,
+                    belief_price: None,
                 },
                 SwapOperation::TerraSwap {
                     offer_asset_info: AssetInfo::Token {
@@ -204,6 +352,8 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
+                    max_spread: None,
+                    belief_price: None,
                 },
                 SwapOperation::TerraSwap {
                     offer_asset_info: AssetInfo::NativeToken {
@@ -212,10 +362,13 @@ fn execute_swap_operations() {
                     ask_asset_info: AssetInfo::Token {
                         contract_addr: "asset0002".to_string(),
                     },
+                    max_spread: None,
+                    belief_price: None,
                 },
             ],
             minimum_receive: None,
             to: Some("addr0002".to_string()),
+            max_spread: None,
         })
         .unwrap(),
     });
@@ -234,6 +387,7 @@ fn execute_swap_operations() {
                         ask_denom: "ukrw".to_string(),
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -248,8 +402,11 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0001".to_string(),
                         },
+                        max_spread: None,
+                        belief_price: None,
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -264,8 +421,11 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
+                        max_spread: None,
+                        belief_price: None,
                     },
                     to: None,
+                    offer_amount: None,
                 })
                 .unwrap(),
             })),
@@ -280,8 +440,11 @@ fn execute_swap_operations() {
                         ask_asset_info: AssetInfo::Token {
                             contract_addr: "asset0002".to_string(),
                         },
+                        max_spread: None,
+                        belief_price: None,
                     },
                     to: Some("addr0002".to_string()),
+                    offer_amount: None,
                 })
                 .unwrap(),
             }))
@@ -289,6 +452,79 @@ fn execute_swap_operations() {
     );
 }
 
+#[test]
+fn execute_swap_operations_invalid_routes() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let uluna = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    let asset0001 = AssetInfo::Token {
+        contract_addr: "asset0001".to_string(),
+    };
+    let terraswap =
+        |offer_asset_info: &AssetInfo, ask_asset_info: &AssetInfo| SwapOperation::TerraSwap {
+            offer_asset_info: offer_asset_info.clone(),
+            ask_asset_info: ask_asset_info.clone(),
+            max_spread: None,
+            belief_price: None,
+        };
+
+    let routes = vec![
+        (
+            vec![terraswap(&uusd, &uluna), terraswap(&asset0001, &uusd)],
+            "invalid operations; operation 1 does not offer the ask asset of operation 0",
+        ),
+        (
+            vec![
+                terraswap(&uusd, &uluna),
+                terraswap(&uluna, &asset0001),
+                terraswap(&asset0001, &uluna),
+            ],
+            "invalid operations; operation 2 swaps back into uluna",
+        ),
+        (
+            vec![
+                terraswap(&uusd, &uluna),
+                SwapOperation::WormHoleBridge {
+                    asset_info: uluna.clone(),
+                    wallet_address_on_target_chain: "wallet0000".to_string(),
+                },
+            ],
+            "invalid operations; operation 1 is not a swap",
+        ),
+    ];
+
+    for (operations, error) in routes {
+        let msg = ExecuteMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive: None,
+            to: None,
+            max_spread: None,
+        };
+        let info = mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000000u128),
+            }],
+        );
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, error),
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
+}
+
 #[test]
 fn execute_swap_operation() {
     let mut deps = mock_dependencies(&[]);
@@ -321,6 +557,7 @@ fn execute_swap_operation() {
             ask_denom: "uluna".to_string(),
         },
         to: None,
+        offer_amount: None,
     };
     let info = mock_info("addr0000", &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
@@ -350,6 +587,7 @@ fn execute_swap_operation() {
             ask_denom: "uluna".to_string(),
         },
         to: Some("addr0000".to_string()),
+        offer_amount: None,
     };
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -379,8 +617,11 @@ fn execute_swap_operation() {
             ask_asset_info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
+            max_spread: None,
+            belief_price: None,
         },
         to: Some("addr0000".to_string()),
+        offer_amount: None,
     };
 
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
@@ -411,6 +652,157 @@ fn execute_swap_operation() {
     );
 }
 
+#[test]
+fn execute_swap_operations_with_max_spread() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let native_swap = SwapOperation::NativeSwap {
+        offer_denom: "uusd".to_string(),
+        ask_denom: "ukrw".to_string(),
+    };
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: vec![
+            native_swap.clone(),
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                ask_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0001".to_string(),
+                },
+                max_spread: None,
+                belief_price: Some(Decimal::percent(200)),
+            },
+            SwapOperation::TerraSwap {
+                offer_asset_info: AssetInfo::Token {
+                    contract_addr: "asset0001".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                max_spread: Some(Decimal::percent(5)),
+                belief_price: None,
+            },
+        ],
+        minimum_receive: None,
+        to: None,
+        max_spread: Some(Decimal::percent(1)),
+    };
+
+    // the global max_spread only fills the hops without their own
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: native_swap,
+                    to: None,
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::TerraSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::Token {
+                            contract_addr: "asset0001".to_string(),
+                        },
+                        max_spread: Some(Decimal::percent(1)),
+                        belief_price: Some(Decimal::percent(200)),
+                    },
+                    to: None,
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::TerraSwap {
+                        offer_asset_info: AssetInfo::Token {
+                            contract_addr: "asset0001".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        max_spread: Some(Decimal::percent(5)),
+                        belief_price: None,
+                    },
+                    to: Some("addr0000".to_string()),
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    // the hop passes both to the pair swap
+    deps.querier
+        .with_terraswap_pairs(&[(&"asset0001uluna".to_string(), &"pair0000".to_string())]);
+    deps.querier.with_token_balances(&[(
+        &"asset0001".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            max_spread: Some(Decimal::percent(5)),
+            belief_price: Some(Decimal::percent(50)),
+        },
+        to: None,
+        offer_amount: None,
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0001".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair0000".to_string(),
+                amount: Uint128::from(1000000u128),
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: "asset0001".to_string(),
+                        },
+                        amount: Uint128::from(1000000u128),
+                    },
+                    belief_price: Some(Decimal::percent(50)),
+                    max_spread: Some(Decimal::percent(5)),
+                    to: None,
+                })
+                .unwrap()
+            })
+            .unwrap()
+        }))]
+    );
+}
+
 #[test]
 fn query_buy_with_routes() {
     let mut deps = mock_dependencies(&[]);
@@ -447,6 +839,8 @@ fn query_buy_with_routes() {
                 ask_asset_info: AssetInfo::Token {
                     contract_addr: "asset0000".to_string(),
                 },
+                max_spread: None,
+                belief_price: None,
             },
             SwapOperation::TerraSwap {
                 offer_asset_info: AssetInfo::Token {
@@ -455,6 +849,8 @@ fn query_buy_with_routes() {
                 ask_asset_info: AssetInfo::NativeToken {
                     denom: "uluna".to_string(),
                 },
+                max_spread: None,
+                belief_price: None,
             },
         ],
     };
@@ -470,6 +866,39 @@ fn query_buy_with_routes() {
         res,
         SimulateSwapOperationsResponse {
             amount: Uint128::from(952380u128), // tax charged 1 times uusd => ukrw, ukrw => asset0000, asset0000 => uluna
+            hops: vec![
+                SwapOperationSimulation {
+                    pair_contract: None,
+                    offer_amount: Uint128::from(1000000u128),
+                    return_amount: Uint128::from(1000000u128),
+                    commission_amount: Uint128::zero(),
+                    spread_amount: Uint128::zero(),
+                    offer_tax_amount: Uint128::zero(),
+                    return_tax_amount: Uint128::zero(),
+                    spot_price: Decimal::one(),
+                },
+                SwapOperationSimulation {
+                    pair_contract: Some("pair0000".to_string()),
+                    offer_amount: Uint128::from(1000000u128),
+                    return_amount: Uint128::from(952380u128),
+                    commission_amount: Uint128::zero(),
+                    spread_amount: Uint128::zero(),
+                    offer_tax_amount: Uint128::from(47620u128),
+                    return_tax_amount: Uint128::zero(),
+                    spot_price: Decimal::one(),
+                },
+                SwapOperationSimulation {
+                    pair_contract: Some("pair0001".to_string()),
+                    offer_amount: Uint128::from(952380u128),
+                    return_amount: Uint128::from(952380u128),
+                    commission_amount: Uint128::zero(),
+                    spread_amount: Uint128::zero(),
+                    offer_tax_amount: Uint128::zero(),
+                    return_tax_amount: Uint128::zero(),
+                    spot_price: Decimal::one(),
+                },
+            ],
+            price_impact: Decimal::from_ratio(47620u128, 1000000u128),
         }
     );
 
@@ -489,46 +918,1154 @@ fn query_buy_with_routes() {
 
     let res: SimulateSwapOperationsResponse =
         from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.amount, Uint128::from(952380u128)); // tax charged 1 times uusd => ukrw, ukrw => uluna
+    assert_eq!(res.hops[1].offer_tax_amount, Uint128::from(47620u128));
+    assert_eq!(
+        res.price_impact,
+        Decimal::from_ratio(47620u128, 1000000u128)
+    );
+
+    let msg = QueryMsg::ReverseSimulateSwapOperations {
+        ask_amount: Uint128::from(952380u128),
+        operations: vec![
+            SwapOperation::NativeSwap {
+                offer_denom: "uusd".to_string(),
+                ask_denom: "ukrw".to_string(),
+            },
+            SwapOperation::NativeSwap {
+                offer_denom: "ukrw".to_string(),
+                ask_denom: "uluna".to_string(),
+            },
+        ],
+    };
+
+    let res: ReverseSimulateSwapOperationsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
     assert_eq!(
         res,
-        SimulateSwapOperationsResponse {
-            amount: Uint128::from(952380u128), // tax charged 1 times uusd => ukrw, ukrw => uluna
+        ReverseSimulateSwapOperationsResponse {
+            offer_amount: Uint128::from(999999u128), // tax charged 1 times on the last swap_send
         }
     );
 }
 
 #[test]
-fn assert_minimum_receive_native_token() {
+fn execute_swap_operations_exact_out() {
     let mut deps = mock_dependencies(&[]);
-    deps.querier.with_balance(&[(
-        "addr0000".to_string(),
-        &[Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(1000000u128),
-        }],
-    )]);
 
-    let info = mock_info("addr0000", &[]);
-    // success
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::NativeToken {
-            denom: "uusd".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000000u128),
-        receiver: "addr0000".to_string(),
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
     };
-    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-    // assertion failed; native token
-    let msg = ExecuteMsg::AssertMinimumReceive {
-        asset_info: AssetInfo::NativeToken {
-            denom: "uusd".to_string(),
-        },
-        prev_balance: Uint128::zero(),
-        minimum_receive: Uint128::from(1000001u128),
-        receiver: "addr0000".to_string(),
-    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &"asset0001".to_string(),
+        &[
+            (&"addr0000".to_string(), &Uint128::from(100u128)),
+            (&"addr0001".to_string(), &Uint128::from(100u128)),
+        ],
+    )]);
+
+    // set tax rate as 5%
+    deps.querier.with_tax(
+        Decimal::percent(5),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_terraswap_pairs(&[
+        (&"asset0000uusd".to_string(), &"pair0000".to_string()),
+        (&"uusdasset0001".to_string(), &"pair0001".to_string()),
+    ]);
+
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+    ];
+
+    // 1000000 asset0001 needs 1050002 uusd with the tax rounded up,
+    // which is 1102504 asset0000 with the tax on the return
+    let msg = QueryMsg::ReverseSimulateSwapOperations {
+        ask_amount: Uint128::from(1000000u128),
+        operations: operations.clone(),
+    };
+    let res: ReverseSimulateSwapOperationsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.offer_amount, Uint128::from(1102504u128));
+
+    // required offer exceeds max_offer
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1200000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperationsExactOut {
+            operations: operations.clone(),
+            ask_amount: Uint128::from(1000000u128),
+            max_offer: Uint128::from(1100000u128),
+            to: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "assertion failed; max offer amount: 1100000, required offer amount: 1102504"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // required offer exceeds the sent amount
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1100000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperationsExactOut {
+            operations: operations.clone(),
+            ask_amount: Uint128::from(1000000u128),
+            max_offer: Uint128::from(1200000u128),
+            to: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "insufficient offer amount; sent amount: 1100000, required offer amount: 1102504"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1200000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperationsExactOut {
+            operations: operations.clone(),
+            ask_amount: Uint128::from(1000000u128),
+            max_offer: Uint128::from(1200000u128),
+            to: Some("addr0001".to_string()),
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[0].clone(),
+                    to: None,
+                    offer_amount: Some(Uint128::from(1102504u128)),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[1].clone(),
+                    to: Some("addr0001".to_string()),
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::Token {
+                        contract_addr: "asset0001".to_string(),
+                    },
+                    prev_balance: Uint128::from(100u128),
+                    minimum_receive: Uint128::from(1000000u128),
+                    receiver: "addr0001".to_string(),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(97496u128),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    // native offer, the refund is sent with the tax deducted
+    let msg = ExecuteMsg::ExecuteSwapOperationsExactOut {
+        operations: vec![operations[1].clone()],
+        ask_amount: Uint128::from(1000000u128),
+        max_offer: Uint128::from(1100000u128),
+        to: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1100000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[1].clone(),
+                    to: Some("addr0000".to_string()),
+                    offer_amount: Some(Uint128::from(1050002u128)),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::Token {
+                        contract_addr: "asset0001".to_string(),
+                    },
+                    prev_balance: Uint128::from(100u128),
+                    minimum_receive: Uint128::from(1000000u128),
+                    receiver: "addr0000".to_string(),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(47617u128),
+                }],
+            })),
+        ]
+    );
+
+    // the pool moved after the reverse simulation and the last hop returned less
+    deps.querier.with_token_balances(&[(
+        &"asset0001".to_string(),
+        &[(&"addr0001".to_string(), &Uint128::from(1000099u128))],
+    )]);
+    let msg = ExecuteMsg::AssertMinimumReceive {
+        asset_info: AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+        prev_balance: Uint128::from(100u128),
+        minimum_receive: Uint128::from(1000000u128),
+        receiver: "addr0001".to_string(),
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "assertion failed; minimum receive amount: 1000000, swap amount: 999999"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn find_best_route() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_terraswap_pairs(&[
+        (&"asset0000asset0001".to_string(), &"pair0000".to_string()),
+        (&"asset0000uusd".to_string(), &"pair0001".to_string()),
+        (&"uusdasset0001".to_string(), &"pair0002".to_string()),
+        (&"ulunaasset0001".to_string(), &"pair0003".to_string()),
+    ]);
+    deps.querier
+        .with_pair_rates(&[(&"pair0001".to_string(), &Decimal::percent(110))]);
+
+    let asset0000 = AssetInfo::Token {
+        contract_addr: "asset0000".to_string(),
+    };
+    let asset0001 = AssetInfo::Token {
+        contract_addr: "asset0001".to_string(),
+    };
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let offer_asset = Asset {
+        info: asset0000.clone(),
+        amount: Uint128::from(1000000u128),
+    };
+
+    // through uusd, the route through uusd and uluna returns the same with one more hop
+    let msg = QueryMsg::FindBestRoute {
+        offer_asset: offer_asset.clone(),
+        ask_asset_info: asset0001.clone(),
+        max_hops: None,
+    };
+    let res: FindBestRouteResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        FindBestRouteResponse {
+            operations: vec![
+                SwapOperation::TerraSwap {
+                    offer_asset_info: asset0000.clone(),
+                    ask_asset_info: uusd.clone(),
+                    max_spread: None,
+                    belief_price: None,
+                },
+                SwapOperation::TerraSwap {
+                    offer_asset_info: uusd,
+                    ask_asset_info: asset0001.clone(),
+                    max_spread: None,
+                    belief_price: None,
+                },
+            ],
+            amount: Uint128::from(1100000u128),
+        }
+    );
+
+    // direct pair only
+    let msg = QueryMsg::FindBestRoute {
+        offer_asset: offer_asset.clone(),
+        ask_asset_info: asset0001.clone(),
+        max_hops: Some(1),
+    };
+    let res: FindBestRouteResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        FindBestRouteResponse {
+            operations: vec![SwapOperation::TerraSwap {
+                offer_asset_info: asset0000,
+                ask_asset_info: asset0001,
+                max_spread: None,
+                belief_price: None,
+            }],
+            amount: Uint128::from(1000000u128),
+        }
+    );
+
+    // no pair reaches the ask asset
+    let msg = QueryMsg::FindBestRoute {
+        offer_asset,
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: "asset0002".to_string(),
+        },
+        max_hops: None,
+    };
+    match query(deps.as_ref(), mock_env(), msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "no route found"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn query_simulation_without_spot_price() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let asset0000 = AssetInfo::Token {
+        contract_addr: "asset0000".to_string(),
+    };
+    let asset0001 = AssetInfo::Token {
+        contract_addr: "asset0001".to_string(),
+    };
+
+    // pair0000 is deployed before the spot price query, so it is priced by its reserves
+    deps.querier
+        .with_terraswap_pairs(&[(&"asset0000asset0001".to_string(), &"pair0000".to_string())]);
+    deps.querier.with_legacy_pools(&[(
+        &"pair0000".to_string(),
+        &[
+            Asset {
+                info: asset0001.clone(),
+                amount: Uint128::from(2000000u128),
+            },
+            Asset {
+                info: asset0000.clone(),
+                amount: Uint128::from(1000000u128),
+            },
+        ],
+    )]);
+
+    let operations = vec![SwapOperation::TerraSwap {
+        offer_asset_info: asset0000.clone(),
+        ask_asset_info: asset0001.clone(),
+        max_spread: None,
+        belief_price: None,
+    }];
+    let msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(1000u128),
+        operations: operations.clone(),
+    };
+    let res: SimulateSwapOperationsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(1000u128),
+            hops: vec![SwapOperationSimulation {
+                pair_contract: Some("pair0000".to_string()),
+                offer_amount: Uint128::from(1000u128),
+                return_amount: Uint128::from(1000u128),
+                commission_amount: Uint128::zero(),
+                spread_amount: Uint128::zero(),
+                offer_tax_amount: Uint128::zero(),
+                return_tax_amount: Uint128::zero(),
+                spot_price: Decimal::percent(200),
+            }],
+            price_impact: Decimal::percent(50),
+        }
+    );
+
+    // the route through the pair is still found
+    let msg = QueryMsg::FindBestRoute {
+        offer_asset: Asset {
+            info: asset0000,
+            amount: Uint128::from(1000u128),
+        },
+        ask_asset_info: asset0001,
+        max_hops: Some(1),
+    };
+    let res: FindBestRouteResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        FindBestRouteResponse {
+            operations,
+            amount: Uint128::from(1000u128),
+        }
+    );
+}
+
+#[test]
+fn execute_distribution() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"asset0001".to_string(),
+        &[(&"addr0001".to_string(), &Uint128::from(100u128))],
+    )]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // set tax rate as 5%
+    deps.querier.with_tax(
+        Decimal::percent(5),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier
+        .with_terraswap_pairs(&[(&"uusdasset0001".to_string(), &"pair0000".to_string())]);
+
+    let token_leg = DistributionLeg {
+        operations: vec![SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        }],
+        recipient: "addr0001".to_string(),
+        minimum_receive: Uint128::from(1000000u128),
+    };
+    let native_leg = DistributionLeg {
+        operations: vec![SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "ukrw".to_string(),
+        }],
+        recipient: "addr0002".to_string(),
+        minimum_receive: Uint128::from(500000u128),
+    };
+
+    // one recipient and ask asset per leg
+    let msg = ExecuteMsg::ExecuteDistribution {
+        legs: vec![token_leg.clone(), token_leg.clone()],
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(3000000u128),
+        }],
+    );
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "invalid legs; duplicate recipient and ask asset")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // two recipients of the same ask asset would swap on the same pair
+    let msg = ExecuteMsg::ExecuteDistribution {
+        legs: vec![
+            token_leg.clone(),
+            DistributionLeg {
+                recipient: "addr0002".to_string(),
+                ..token_leg.clone()
+            },
+        ],
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(3000000u128),
+        }],
+    );
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "invalid legs; legs must not share a pair: asset0001-uusd"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // 1050002 uusd for the token leg and 525000 uusd for the native leg
+    let msg = ExecuteMsg::ExecuteDistribution {
+        legs: vec![token_leg.clone(), native_leg.clone()],
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+    match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "insufficient offer amount; sent amount: 1000000, required offer amount: 1575002"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(3000000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: token_leg.operations[0].clone(),
+                    to: Some("addr0001".to_string()),
+                    offer_amount: Some(Uint128::from(1050002u128)),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::Token {
+                        contract_addr: "asset0001".to_string(),
+                    },
+                    prev_balance: Uint128::from(100u128),
+                    minimum_receive: Uint128::from(1000000u128),
+                    receiver: "addr0001".to_string(),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: native_leg.operations[0].clone(),
+                    to: Some("addr0002".to_string()),
+                    offer_amount: Some(Uint128::from(525000u128)),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: Uint128::from(500000u128),
+                    receiver: "addr0002".to_string(),
+                })
+                .unwrap(),
+            })),
+            // 1424998 uusd left, sent with the tax deducted
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(1357140u128),
+                }],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn execute_split_swap() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"asset0001".to_string(),
+        &[(&"addr0000".to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let asset0000 = AssetInfo::Token {
+        contract_addr: "asset0000".to_string(),
+    };
+    let asset0001 = AssetInfo::Token {
+        contract_addr: "asset0001".to_string(),
+    };
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let direct_route = vec![SwapOperation::TerraSwap {
+        offer_asset_info: asset0000.clone(),
+        ask_asset_info: asset0001.clone(),
+        max_spread: None,
+        belief_price: None,
+    }];
+    let uusd_route = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: asset0000.clone(),
+            ask_asset_info: uusd.clone(),
+            max_spread: None,
+            belief_price: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: uusd,
+            ask_asset_info: asset0001.clone(),
+            max_spread: None,
+            belief_price: None,
+        },
+    ];
+
+    // fractions must sum up to one
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteSplitSwap {
+            routes: vec![
+                (Decimal::percent(30), direct_route.clone()),
+                (Decimal::percent(60), uusd_route.clone()),
+            ],
+            minimum_receive: None,
+            to: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "invalid routes; fractions must sum up to one")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // the received token must be the offer asset
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteSplitSwap {
+            routes: vec![
+                (Decimal::percent(30), direct_route.clone()),
+                (Decimal::percent(70), uusd_route.clone()),
+            ],
+            minimum_receive: None,
+            to: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0002", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "invalid operations; the received token is not the offer asset"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000001u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteSplitSwap {
+            routes: vec![
+                (Decimal::percent(30), direct_route.clone()),
+                (Decimal::percent(70), uusd_route.clone()),
+            ],
+            minimum_receive: Some(Uint128::from(1000000u128)),
+            to: None,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: direct_route[0].clone(),
+                    to: Some("addr0000".to_string()),
+                    offer_amount: Some(Uint128::from(300000u128)),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: uusd_route[0].clone(),
+                    to: None,
+                    // the last route takes the rounding remainder
+                    offer_amount: Some(Uint128::from(700001u128)),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: uusd_route[1].clone(),
+                    to: Some("addr0000".to_string()),
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: asset0001,
+                    prev_balance: Uint128::from(1000000u128),
+                    minimum_receive: Uint128::from(1000000u128),
+                    receiver: "addr0000".to_string(),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    // the first hop swaps only the given amount
+    deps.querier
+        .with_terraswap_pairs(&[(&"asset0000asset0001".to_string(), &"pair0000".to_string())]);
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: direct_route[0].clone(),
+        to: Some("addr0000".to_string()),
+        offer_amount: Some(Uint128::from(300000u128)),
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair0000".to_string(),
+                amount: Uint128::from(300000u128),
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: asset0000,
+                        amount: Uint128::from(300000u128),
+                    },
+                    belief_price: None,
+                    max_spread: None,
+                    to: Some("addr0000".to_string()),
+                })
+                .unwrap()
+            })
+            .unwrap()
+        }))]
+    );
+}
+
+#[test]
+fn query_split_swap() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_terraswap_pairs(&[
+        (&"asset0000asset0001".to_string(), &"pair0000".to_string()),
+        (&"asset0000asset0002".to_string(), &"pair0001".to_string()),
+        (&"asset0002asset0001".to_string(), &"pair0002".to_string()),
+    ]);
+    deps.querier
+        .with_pair_rates(&[(&"pair0001".to_string(), &Decimal::percent(110))]);
+
+    let asset0000 = AssetInfo::Token {
+        contract_addr: "asset0000".to_string(),
+    };
+    let asset0001 = AssetInfo::Token {
+        contract_addr: "asset0001".to_string(),
+    };
+    let asset0002 = AssetInfo::Token {
+        contract_addr: "asset0002".to_string(),
+    };
+
+    let msg = QueryMsg::SimulateSplitSwap {
+        offer_amount: Uint128::from(1000000u128),
+        routes: vec![
+            (
+                Decimal::percent(40),
+                vec![SwapOperation::TerraSwap {
+                    offer_asset_info: asset0000.clone(),
+                    ask_asset_info: asset0001.clone(),
+                    max_spread: None,
+                    belief_price: None,
+                }],
+            ),
+            (
+                Decimal::percent(60),
+                vec![
+                    SwapOperation::TerraSwap {
+                        offer_asset_info: asset0000.clone(),
+                        ask_asset_info: asset0002.clone(),
+                        max_spread: None,
+                        belief_price: None,
+                    },
+                    SwapOperation::TerraSwap {
+                        offer_asset_info: asset0002.clone(),
+                        ask_asset_info: asset0001.clone(),
+                        max_spread: None,
+                        belief_price: None,
+                    },
+                ],
+            ),
+        ],
+    };
+    let res: SimulateSplitSwapResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SimulateSplitSwapResponse {
+            amounts: vec![Uint128::from(400000u128), Uint128::from(660000u128)],
+            amount: Uint128::from(1060000u128),
+        }
+    );
+
+    // routes must end in the same ask asset
+    let msg = QueryMsg::SimulateSplitSwap {
+        offer_amount: Uint128::from(1000000u128),
+        routes: vec![
+            (
+                Decimal::percent(40),
+                vec![SwapOperation::TerraSwap {
+                    offer_asset_info: asset0000.clone(),
+                    ask_asset_info: asset0001,
+                    max_spread: None,
+                    belief_price: None,
+                }],
+            ),
+            (
+                Decimal::percent(60),
+                vec![SwapOperation::TerraSwap {
+                    offer_asset_info: asset0000,
+                    ask_asset_info: asset0002,
+                    max_spread: None,
+                    belief_price: None,
+                }],
+            ),
+        ],
+    };
+    match query(deps.as_ref(), mock_env(), msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "invalid routes; routes must share the offer and ask asset"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn execute_multi_swap() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"asset0001".to_string(),
+        &[(&"addr0000".to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let uusd_operation = SwapOperation::TerraSwap {
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+        max_spread: None,
+        belief_price: None,
+    };
+    let asset0000_operation = SwapOperation::TerraSwap {
+        offer_asset_info: AssetInfo::Token {
+            contract_addr: "asset0000".to_string(),
+        },
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: "asset0001".to_string(),
+        },
+        max_spread: None,
+        belief_price: None,
+    };
+    let inputs = vec![
+        SwapInput {
+            offer_asset: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(1000u128),
+            },
+            operations: vec![uusd_operation.clone()],
+        },
+        SwapInput {
+            offer_asset: Asset {
+                info: AssetInfo::Token {
+                    contract_addr: "asset0000".to_string(),
+                },
+                amount: Uint128::from(2000u128),
+            },
+            operations: vec![asset0000_operation.clone()],
+        },
+    ];
+
+    // the native offer must be sent
+    let msg = ExecuteMsg::ExecuteMultiSwap {
+        inputs: inputs.clone(),
+        minimum_receive: None,
+        to: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "Native token balance mismatch between the argument and the transferred"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // inputs must end in the same asset
+    let msg = ExecuteMsg::ExecuteMultiSwap {
+        inputs: vec![
+            inputs[0].clone(),
+            SwapInput {
+                offer_asset: inputs[1].offer_asset.clone(),
+                operations: vec![SwapOperation::TerraSwap {
+                    offer_asset_info: AssetInfo::Token {
+                        contract_addr: "asset0000".to_string(),
+                    },
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    max_spread: None,
+                    belief_price: None,
+                }],
+            },
+        ],
+        minimum_receive: None,
+        to: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "invalid inputs; inputs must share the ask asset")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // funds not matching a native input are rejected
+    let msg = ExecuteMsg::ExecuteMultiSwap {
+        inputs: inputs.clone(),
+        minimum_receive: None,
+        to: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000u128),
+            },
+            Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::from(500u128),
+            },
+        ],
+    );
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "invalid funds; 500uluna does not match any native input"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::ExecuteMultiSwap {
+        inputs,
+        minimum_receive: Some(Uint128::from(2500u128)),
+        to: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr0000".to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::from(2000u128),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: uusd_operation,
+                    to: Some("addr0000".to_string()),
+                    offer_amount: Some(Uint128::from(1000u128)),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: asset0000_operation,
+                    to: Some("addr0000".to_string()),
+                    offer_amount: Some(Uint128::from(2000u128)),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::Token {
+                        contract_addr: "asset0001".to_string(),
+                    },
+                    prev_balance: Uint128::from(1000000u128),
+                    minimum_receive: Uint128::from(2500u128),
+                    receiver: "addr0000".to_string(),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+}
+
+#[test]
+fn assert_minimum_receive_native_token() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance(&[(
+        "addr0000".to_string(),
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    )]);
+
+    let info = mock_info("addr0000", &[]);
+    // success
+    let msg = ExecuteMsg::AssertMinimumReceive {
+        asset_info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        prev_balance: Uint128::zero(),
+        minimum_receive: Uint128::from(1000000u128),
+        receiver: "addr0000".to_string(),
+    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // assertion failed; native token
+    let msg = ExecuteMsg::AssertMinimumReceive {
+        asset_info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        prev_balance: Uint128::zero(),
+        minimum_receive: Uint128::from(1000001u128),
+        receiver: "addr0000".to_string(),
+    };
     let res = execute(deps.as_mut(), mock_env(), info, msg);
     match res {
         Err(StdError::GenericErr { msg, .. }) => assert_eq!(
@@ -577,3 +2114,559 @@ fn assert_minimum_receive_token() {
         _ => panic!("DO NOT ENTER HERE"),
     }
 }
+
+#[test]
+fn update_allowed_pools() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateAllowedPools {
+        add: vec![
+            AllowedPool {
+                pair_address: "pool0000".to_string(),
+                protocol: "terraswap".to_string(),
+            },
+            AllowedPool {
+                pair_address: "pool0001".to_string(),
+                protocol: "astro".to_string(),
+            },
+        ],
+        remove: vec![],
+    };
+    let info = mock_info("addr0001", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the terraswap protocol takes no adapter
+    let msg = ExecuteMsg::UpdateAdapters {
+        add: vec![ProtocolAdapter {
+            protocol: "terraswap".to_string(),
+            adapter: "adapter0000".to_string(),
+        }],
+        remove: vec![],
+    };
+    let info = mock_info("addr0000", &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "the terraswap protocol is swapped without an adapter")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::UpdateAdapters {
+        add: vec![ProtocolAdapter {
+            protocol: "astro".to_string(),
+            adapter: "adapter0000".to_string(),
+        }],
+        remove: vec![],
+    };
+    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res: AllowedPoolsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllowedPools {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    // in the order of the canonical addresses
+    assert_eq!(
+        res.pools,
+        vec![
+            AllowedPool {
+                pair_address: "pool0001".to_string(),
+                protocol: "astro".to_string(),
+            },
+            AllowedPool {
+                pair_address: "pool0000".to_string(),
+                protocol: "terraswap".to_string(),
+            },
+        ]
+    );
+
+    let res: AdaptersResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Adapters {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.adapters,
+        vec![ProtocolAdapter {
+            protocol: "astro".to_string(),
+            adapter: "adapter0000".to_string(),
+        }]
+    );
+
+    let msg = ExecuteMsg::UpdateAllowedPools {
+        add: vec![],
+        remove: vec!["pool0000".to_string()],
+    };
+    let info = mock_info("addr0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res: AllowedPoolsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllowedPools {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.pools,
+        vec![AllowedPool {
+            pair_address: "pool0001".to_string(),
+            protocol: "astro".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn execute_pool_operation() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_tax(
+        Decimal::percent(5),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_balance(&[(
+        MOCK_CONTRACT_ADDR.to_string(),
+        &[Coin {
+            amount: Uint128::from(1000000u128),
+            denom: "uusd".to_string(),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[(
+        &"asset".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::Pool {
+            pair_address: "pool0000".to_string(),
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset".to_string(),
+            },
+            protocol: "terraswap".to_string(),
+            max_spread: None,
+            belief_price: None,
+        },
+        to: None,
+        offer_amount: None,
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "pool is not allowed: pool0000"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("addr0000", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::UpdateAllowedPools {
+            add: vec![
+                AllowedPool {
+                    pair_address: "pool0000".to_string(),
+                    protocol: "terraswap".to_string(),
+                },
+                AllowedPool {
+                    pair_address: "pool0001".to_string(),
+                    protocol: "astro".to_string(),
+                },
+            ],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+
+    // a pool speaking the terraswap pair interface is swapped on directly
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pool0000".to_string(),
+            funds: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(952380u128), // deduct tax
+            }],
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(952380u128),
+                },
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            })
+            .unwrap(),
+        }))],
+    );
+
+    // the protocol has to match the allowed one
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::Pool {
+            pair_address: "pool0001".to_string(),
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            protocol: "terraswap".to_string(),
+            max_spread: None,
+            belief_price: None,
+        },
+        to: Some("addr0000".to_string()),
+        offer_amount: None,
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "pool is allowed for another protocol: astro")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::Pool {
+            pair_address: "pool0001".to_string(),
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            protocol: "astro".to_string(),
+            max_spread: Some(Decimal::percent(5)),
+            belief_price: Some(Decimal::percent(50)),
+        },
+        to: Some("addr0000".to_string()),
+        offer_amount: None,
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    match execute(deps.as_mut(), mock_env(), info, msg.clone()) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "no adapter for astro"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info("addr0000", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::UpdateAdapters {
+            add: vec![ProtocolAdapter {
+                protocol: "astro".to_string(),
+                adapter: "adapter0000".to_string(),
+            }],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+
+    // other protocols are swapped through their adapter
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "adapter0000".to_string(),
+                amount: Uint128::from(1000000u128),
+                msg: to_binary(&AdapterCw20HookMsg::Swap {
+                    pair_address: "pool0001".to_string(),
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    max_spread: Some(Decimal::percent(5)),
+                    belief_price: Some(Decimal::percent(50)),
+                    to: Some("addr0000".to_string()),
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+        }))],
+    );
+
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::Pool {
+            pair_address: "pool0001".to_string(),
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset".to_string(),
+            },
+            protocol: "astro".to_string(),
+            max_spread: None,
+            belief_price: None,
+        },
+        to: None,
+        offer_amount: Some(Uint128::from(500000u128)),
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "adapter0000".to_string(),
+            funds: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(476190u128), // deduct tax
+            }],
+            msg: to_binary(&AdapterExecuteMsg::Swap {
+                pair_address: "pool0001".to_string(),
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(476190u128),
+                },
+                ask_asset_info: AssetInfo::Token {
+                    contract_addr: "asset".to_string(),
+                },
+                max_spread: None,
+                belief_price: None,
+                to: None,
+            })
+            .unwrap(),
+        }))],
+    );
+
+    // the global max_spread is forwarded to the pool swap
+    let pool_operation = SwapOperation::Pool {
+        pair_address: "pool0000".to_string(),
+        offer_asset_info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: "asset".to_string(),
+        },
+        protocol: "terraswap".to_string(),
+        max_spread: Some(Decimal::percent(1)),
+        belief_price: None,
+    };
+    let msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: vec![SwapOperation::Pool {
+            pair_address: "pool0000".to_string(),
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset".to_string(),
+            },
+            protocol: "terraswap".to_string(),
+            max_spread: None,
+            belief_price: None,
+        }],
+        minimum_receive: None,
+        to: None,
+        max_spread: Some(Decimal::percent(1)),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                operation: pool_operation.clone(),
+                to: Some("addr0000".to_string()),
+                offer_amount: None,
+            })
+            .unwrap(),
+        }))],
+    );
+
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: pool_operation,
+        to: Some("addr0000".to_string()),
+        offer_amount: None,
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pool0000".to_string(),
+            funds: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(952380u128), // deduct tax
+            }],
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(952380u128),
+                },
+                belief_price: None,
+                max_spread: Some(Decimal::percent(1)),
+                to: Some("addr0000".to_string()),
+            })
+            .unwrap(),
+        }))],
+    );
+}
+
+#[test]
+fn query_pool_simulation() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        terraswap_factory: "terraswapfactory".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("addr0000", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::UpdateAllowedPools {
+            add: vec![
+                AllowedPool {
+                    pair_address: "pool0000".to_string(),
+                    protocol: "terraswap".to_string(),
+                },
+                AllowedPool {
+                    pair_address: "pool0001".to_string(),
+                    protocol: "astro".to_string(),
+                },
+            ],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+    let info = mock_info("addr0000", &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::UpdateAdapters {
+            add: vec![ProtocolAdapter {
+                protocol: "astro".to_string(),
+                adapter: "adapter0000".to_string(),
+            }],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+
+    // the adapter answers the queries of its pools
+    deps.querier.with_pair_rates(&[
+        (&"pool0000".to_string(), &Decimal::percent(200)),
+        (&"adapter0000".to_string(), &Decimal::percent(50)),
+    ]);
+
+    let operations = vec![
+        SwapOperation::Pool {
+            pair_address: "pool0000".to_string(),
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            protocol: "terraswap".to_string(),
+            max_spread: None,
+            belief_price: None,
+        },
+        SwapOperation::Pool {
+            pair_address: "pool0001".to_string(),
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0002".to_string(),
+            },
+            protocol: "astro".to_string(),
+            max_spread: None,
+            belief_price: None,
+        },
+    ];
+
+    let res: SimulateSwapOperationsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateSwapOperations {
+                offer_amount: Uint128::from(1000000u128),
+                operations: operations.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.amount, Uint128::from(1000000u128));
+    assert_eq!(
+        res.hops
+            .iter()
+            .map(|hop| (hop.pair_contract.clone(), hop.return_amount))
+            .collect::<Vec<_>>(),
+        vec![
+            (Some("pool0000".to_string()), Uint128::from(2000000u128)),
+            (Some("pool0001".to_string()), Uint128::from(1000000u128)),
+        ]
+    );
+
+    let res: ReverseSimulateSwapOperationsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ReverseSimulateSwapOperations {
+                ask_amount: Uint128::from(1000000u128),
+                operations,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    // one more for the rounding of each pair
+    assert_eq!(res.offer_amount, Uint128::from(1000001u128));
+}
@@ -1,15 +1,25 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+};
 
 use cw2::set_contract_version;
+use cw20::MarketingInfoResponse;
+use cw20_base::{
+    contract::{
+        execute_update_marketing, execute_upload_logo, query_download_logo, query_marketing_info,
+    },
+    msg::{ExecuteMsg, QueryMsg},
+    state::MARKETING_INFO,
+};
 use cw20_legacy::{
     contract::{create_accounts, execute as cw20_execute, query as cw20_query},
-    msg::{ExecuteMsg, QueryMsg},
+    msg::{ExecuteMsg as LegacyExecuteMsg, QueryMsg as LegacyQueryMsg},
     state::{MinterData, TokenInfo, TOKEN_INFO},
-    ContractError,
 };
 
+use crate::error::ContractError;
 use terraswap::token::InstantiateMsg;
 
 // version info for migration info
@@ -19,10 +29,10 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     // check valid token info
     msg.validate()?;
@@ -32,7 +42,7 @@ pub fn instantiate(
 
     if let Some(limit) = msg.get_cap() {
         if total_supply > limit {
-            return Err(StdError::generic_err("Initial supply greater than cap"));
+            return Err(StdError::generic_err("Initial supply greater than cap").into());
         }
     }
 
@@ -54,6 +64,39 @@ pub fn instantiate(
     };
 
     TOKEN_INFO.save(deps.storage, &data)?;
+
+    if let Some(marketing) = msg.marketing {
+        let marketing_addr = marketing
+            .marketing
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+
+        // the logo is uploaded on behalf of the token itself,
+        // so it is validated by cw20-base before the marketing address is set
+        MARKETING_INFO.save(
+            deps.storage,
+            &MarketingInfoResponse {
+                project: marketing.project,
+                description: marketing.description,
+                marketing: Some(env.contract.address.clone()),
+                logo: None,
+            },
+        )?;
+
+        if let Some(logo) = marketing.logo {
+            let info = MessageInfo {
+                sender: env.contract.address.clone(),
+                funds: vec![],
+            };
+            execute_upload_logo(deps.branch(), env, info, logo)?;
+        }
+
+        MARKETING_INFO.update(deps.storage, |mut data| -> StdResult<_> {
+            data.marketing = marketing_addr;
+            Ok(data)
+        })?;
+    }
+
     Ok(Response::default())
 }
 
@@ -64,10 +107,102 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    cw20_execute(deps, env, info, msg)
+    let legacy_msg = match msg {
+        ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            marketing,
+        } => {
+            return Ok(execute_update_marketing(
+                deps,
+                env,
+                info,
+                project,
+                description,
+                marketing,
+            )?)
+        }
+        ExecuteMsg::UploadLogo(logo) => return Ok(execute_upload_logo(deps, env, info, logo)?),
+        ExecuteMsg::Transfer { recipient, amount } => {
+            LegacyExecuteMsg::Transfer { recipient, amount }
+        }
+        ExecuteMsg::Burn { amount } => LegacyExecuteMsg::Burn { amount },
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => LegacyExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        },
+        ExecuteMsg::Mint { recipient, amount } => LegacyExecuteMsg::Mint { recipient, amount },
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => LegacyExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        },
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => LegacyExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        },
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => LegacyExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        },
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => LegacyExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        },
+        ExecuteMsg::BurnFrom { owner, amount } => LegacyExecuteMsg::BurnFrom { owner, amount },
+    };
+
+    Ok(cw20_execute(deps, env, info, legacy_msg)?)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    cw20_query(deps, env, msg)
+    let legacy_msg = match msg {
+        QueryMsg::MarketingInfo {} => return to_binary(&query_marketing_info(deps)?),
+        QueryMsg::DownloadLogo {} => return to_binary(&query_download_logo(deps)?),
+        QueryMsg::Balance { address } => LegacyQueryMsg::Balance { address },
+        QueryMsg::TokenInfo {} => LegacyQueryMsg::TokenInfo {},
+        QueryMsg::Minter {} => LegacyQueryMsg::Minter {},
+        QueryMsg::Allowance { owner, spender } => LegacyQueryMsg::Allowance { owner, spender },
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => LegacyQueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        },
+        QueryMsg::AllAccounts { start_after, limit } => {
+            LegacyQueryMsg::AllAccounts { start_after, limit }
+        }
+    };
+
+    cw20_query(deps, env, legacy_msg)
 }
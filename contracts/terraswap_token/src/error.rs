@@ -0,0 +1,14 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Legacy(#[from] cw20_legacy::ContractError),
+
+    #[error("{0}")]
+    Base(#[from] cw20_base::ContractError),
+}
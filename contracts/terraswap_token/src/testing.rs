@@ -0,0 +1,253 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{from_binary, Addr, Binary, DepsMut, OwnedDeps, Response, Uint128};
+use cw20::{
+    BalanceResponse, Cw20Coin, DownloadLogoResponse, EmbeddedLogo, Logo, LogoInfo,
+    MarketingInfoResponse, TokenInfoResponse,
+};
+use cw20_base::msg::{ExecuteMsg, QueryMsg};
+use cw20_base::ContractError as Cw20BaseError;
+use terraswap::token::{InstantiateMarketingInfo, InstantiateMsg};
+
+// PNG header: magic byte, "PNG", dos line ending, dos eof, unix line ending
+const PNG_HEADER: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+fn instantiate_token(
+    deps: DepsMut,
+    marketing: Option<InstantiateMarketingInfo>,
+) -> Result<Response, ContractError> {
+    let msg = InstantiateMsg {
+        name: "terraswap liquidity token".to_string(),
+        symbol: "uLP".to_string(),
+        decimals: 6,
+        initial_balances: vec![Cw20Coin {
+            address: "addr0000".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+        mint: None,
+        marketing,
+    };
+
+    instantiate(deps, mock_env(), mock_info("creator", &[]), msg)
+}
+
+fn marketing_info(logo: Option<Logo>) -> InstantiateMarketingInfo {
+    InstantiateMarketingInfo {
+        project: Some("terraswap".to_string()),
+        description: Some("liquidity token".to_string()),
+        marketing: Some("marketing0000".to_string()),
+        logo,
+    }
+}
+
+fn query_marketing_info(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> MarketingInfoResponse {
+    from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::MarketingInfo {}).unwrap()).unwrap()
+}
+
+#[test]
+fn instantiate_with_marketing() {
+    let mut deps = mock_dependencies(&[]);
+
+    let png = Binary::from([&PNG_HEADER[..], &[0u8; 8][..]].concat());
+    instantiate_token(
+        deps.as_mut(),
+        Some(marketing_info(Some(Logo::Embedded(EmbeddedLogo::Png(
+            png.clone(),
+        ))))),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_marketing_info(&deps),
+        MarketingInfoResponse {
+            project: Some("terraswap".to_string()),
+            description: Some("liquidity token".to_string()),
+            marketing: Some(Addr::unchecked("marketing0000")),
+            logo: Some(LogoInfo::Embedded),
+        }
+    );
+
+    let res: DownloadLogoResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::DownloadLogo {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        DownloadLogoResponse {
+            mime_type: "image/png".to_string(),
+            data: png,
+        }
+    );
+
+    // without marketing info
+    let mut deps = mock_dependencies(&[]);
+    instantiate_token(deps.as_mut(), None).unwrap();
+    assert_eq!(
+        query_marketing_info(&deps),
+        MarketingInfoResponse::default()
+    );
+}
+
+#[test]
+fn instantiate_with_invalid_logo() {
+    let mut deps = mock_dependencies(&[]);
+
+    // logo over the size cap
+    let big_png = Binary::from([&PNG_HEADER[..], &[0u8; 6 * 1024][..]].concat());
+    let res = instantiate_token(
+        deps.as_mut(),
+        Some(marketing_info(Some(Logo::Embedded(EmbeddedLogo::Png(
+            big_png,
+        ))))),
+    );
+    assert_eq!(res, Err(ContractError::Base(Cw20BaseError::LogoTooBig {})));
+
+    // png without the png header
+    let res = instantiate_token(
+        deps.as_mut(),
+        Some(marketing_info(Some(Logo::Embedded(EmbeddedLogo::Png(
+            Binary::from(b"not a png".to_vec()),
+        ))))),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::Base(Cw20BaseError::InvalidPngHeader {}))
+    );
+
+    // svg without the xml preamble
+    let res = instantiate_token(
+        deps.as_mut(),
+        Some(marketing_info(Some(Logo::Embedded(EmbeddedLogo::Svg(
+            Binary::from(b"<svg></svg>".to_vec()),
+        ))))),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::Base(Cw20BaseError::InvalidXmlPreamble {}))
+    );
+}
+
+#[test]
+fn update_marketing() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_token(deps.as_mut(), Some(marketing_info(None))).unwrap();
+
+    let msg = ExecuteMsg::UpdateMarketing {
+        project: Some("terraswap v2".to_string()),
+        description: None,
+        marketing: None,
+    };
+
+    // only the marketing address can update the marketing info
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::Base(Cw20BaseError::Unauthorized {}))
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("marketing0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_marketing_info(&deps),
+        MarketingInfoResponse {
+            project: Some("terraswap v2".to_string()),
+            description: Some("liquidity token".to_string()),
+            marketing: Some(Addr::unchecked("marketing0000")),
+            logo: None,
+        }
+    );
+
+    let msg = ExecuteMsg::UploadLogo(Logo::Url("https://terraswap.io/logo.png".to_string()));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    assert_eq!(
+        res,
+        Err(ContractError::Base(Cw20BaseError::Unauthorized {}))
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("marketing0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        query_marketing_info(&deps).logo,
+        Some(LogoInfo::Url("https://terraswap.io/logo.png".to_string()))
+    );
+
+    // logo over the size cap
+    let big_svg = Binary::from([&b"<?xml version=\"1.0\"?>"[..], &[b' '; 6 * 1024][..]].concat());
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("marketing0000", &[]),
+        ExecuteMsg::UploadLogo(Logo::Embedded(EmbeddedLogo::Svg(big_svg))),
+    );
+    assert_eq!(res, Err(ContractError::Base(Cw20BaseError::LogoTooBig {})));
+}
+
+#[test]
+fn transfer_and_query() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate_token(deps.as_mut(), None).unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Transfer {
+            recipient: "addr0001".to_string(),
+            amount: Uint128::from(300u128),
+        },
+    )
+    .unwrap();
+
+    let balance = |address: &str| -> Uint128 {
+        let res: BalanceResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        res.balance
+    };
+    assert_eq!(balance("addr0000"), Uint128::from(700u128));
+    assert_eq!(balance("addr0001"), Uint128::from(300u128));
+
+    let res: TokenInfoResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        TokenInfoResponse {
+            name: "terraswap liquidity token".to_string(),
+            symbol: "uLP".to_string(),
+            decimals: 6,
+            total_supply: Uint128::from(1000u128),
+        }
+    );
+}
@@ -2,7 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-use crate::querier::{query_balance, query_token_balance};
+use crate::querier::{query_balance, query_token_balance, query_token_info};
 use cosmwasm_std::{
     to_binary, Addr, Api, BankMsg, CanonicalAddr, Coin, CosmosMsg, Decimal, MessageInfo,
     QuerierWrapper, StdError, StdResult, SubMsg, Uint128, WasmMsg,
@@ -178,6 +178,17 @@ impl AssetInfo {
         }
     }
 
+    /// Returns the ticker of the asset, queried from the token contract
+    /// or derived from the denom for native tokens
+    pub fn query_symbol(&self, querier: &QuerierWrapper, api: &dyn Api) -> StdResult<String> {
+        match self {
+            AssetInfo::Token { contract_addr, .. } => {
+                Ok(query_token_info(querier, api.addr_validate(contract_addr.as_str())?)?.symbol)
+            }
+            AssetInfo::NativeToken { denom, .. } => Ok(native_symbol(denom)),
+        }
+    }
+
     pub fn equal(&self, asset: &AssetInfo) -> bool {
         match self {
             AssetInfo::Token { contract_addr, .. } => {
@@ -198,6 +209,20 @@ impl AssetInfo {
     }
 }
 
+/// Maps terra native denoms to their tickers, e.g. uluna => LUNA, uusd => UST, ukrw => KRT
+pub fn native_symbol(denom: &str) -> String {
+    if denom == "uluna" {
+        return "LUNA".to_string();
+    }
+
+    match denom.strip_prefix('u') {
+        Some(fiat) if fiat.len() == 3 && fiat.chars().all(|c| c.is_ascii_alphabetic()) => {
+            format!("{}T", fiat[..2].to_uppercase())
+        }
+        _ => denom.to_uppercase(),
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AssetRaw {
     pub info: AssetInfoRaw,
@@ -273,6 +298,7 @@ pub struct PairInfo {
     pub asset_infos: [AssetInfo; 2],
     pub contract_addr: String,
     pub liquidity_token: String,
+    pub asset_decimals: [u8; 2],
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -280,6 +306,9 @@ pub struct PairInfoRaw {
     pub asset_infos: [AssetInfoRaw; 2],
     pub contract_addr: CanonicalAddr,
     pub liquidity_token: CanonicalAddr,
+    /// Missing in the pairs stored before the decimals registry
+    #[serde(default)]
+    pub asset_decimals: [u8; 2],
 }
 
 impl PairInfoRaw {
@@ -291,6 +320,7 @@ impl PairInfoRaw {
                 self.asset_infos[0].to_normal(api)?,
                 self.asset_infos[1].to_normal(api)?,
             ],
+            asset_decimals: self.asset_decimals,
         })
     }
 
@@ -1,7 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::asset::{AssetInfo, PairInfo};
+use crate::asset::{Asset, AssetInfo, PairInfo};
+use crate::ownership::OwnershipProposalResponse;
+use crate::pair::{PairType, PoolResponse};
+use cosmwasm_std::Decimal;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -15,17 +18,84 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// UpdateConfig update relevant code IDs
     UpdateConfig {
-        owner: Option<String>,
         token_code_id: Option<u64>,
         pair_code_id: Option<u64>,
+        pair_creation_mode: Option<PairCreationMode>,
     },
+    /// DeregisterPair removes the pair from the registry and keeps it in the retired pairs
+    DeregisterPair { asset_infos: [AssetInfo; 2] },
+    /// RegisterPair registers a pair deployed outside of the factory
+    RegisterPair { pair_info: PairInfo },
+    /// UpdateAllowedCreators adds and removes the creators allowed in `Allowlist` mode
+    UpdateAllowedCreators {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// UpdateApprovedTokens adds and removes the assets allowed in `ApprovedTokens` mode
+    UpdateApprovedTokens {
+        add: Vec<AssetInfo>,
+        remove: Vec<AssetInfo>,
+    },
+    /// AddNativeTokenDecimals registers the decimals of a native token,
+    /// a deposit of the token must be sent along as a proof of its existence
+    AddNativeTokenDecimals { denom: String, decimals: u8 },
+    /// ProposeNewOwner creates a proposal to hand over the ownership,
+    /// which has to be claimed by the new owner within `expires_in` seconds
+    ProposeNewOwner { owner: String, expires_in: u64 },
+    /// ClaimOwnership accepts the pending ownership proposal
+    ClaimOwnership {},
+    /// DropOwnershipProposal removes the pending ownership proposal
+    DropOwnershipProposal {},
+    /// RenounceOwnership removes the owner, leaving the config immutable
+    RenounceOwnership {},
     /// CreatePair instantiates pair contract
     CreatePair {
         /// Asset infos
         asset_infos: [AssetInfo; 2],
+        /// Pricing of the pair, defaults to constant product.
+        /// Only the owner can create pairs of another type
+        pair_type: Option<PairType>,
+    },
+    /// CreatePairAndProvide instantiates pair contract and provides the initial liquidity
+    /// in the same transaction. Cw20 assets must be approved to the factory beforehand.
+    CreatePairAndProvide {
+        /// Asset infos
+        asset_infos: [AssetInfo; 2],
+        /// Pricing of the pair, defaults to constant product.
+        /// Only the owner can create pairs of another type
+        pair_type: Option<PairType>,
+        /// Initial liquidity, which sets the initial price
+        assets: [Asset; 2],
+        slippage_tolerance: Option<Decimal>,
+        /// LP token receiver, defaults to the sender
+        receiver: Option<String>,
+    },
+}
+
+/// Who can create pairs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairCreationMode {
+    /// Anyone can create a pair
+    Permissionless {},
+    /// Only the owner and the allowed creators can create a pair
+    Allowlist {},
+    /// Anyone can create a pair of two approved assets
+    ApprovedTokens {},
+    /// Anyone can create a pair by paying the fee, which is sent to the fee collector
+    Fee {
+        /// Native token fee
+        fee: Asset,
+        fee_collector: String,
     },
 }
 
+impl Default for PairCreationMode {
+    fn default() -> Self {
+        PairCreationMode::Permissionless {}
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
@@ -36,23 +106,128 @@ pub enum QueryMsg {
     Pairs {
         start_after: Option<[AssetInfo; 2]>,
         limit: Option<u32>,
+        /// Queries the pool of each pair
+        include_pools: Option<bool>,
+    },
+    /// Number of the registered pairs
+    PairCount {},
+    /// Pairs in the order of their registration
+    PairsByCreation {
+        start_after_index: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Pair by the pair contract address
+    PairByAddress {
+        address: String,
+    },
+    /// Pair by its LP token address
+    PairByLiquidityToken {
+        token: String,
+    },
+    /// Pairs containing the asset
+    PairsByAsset {
+        asset_info: AssetInfo,
+        start_after: Option<[AssetInfo; 2]>,
+        limit: Option<u32>,
+    },
+    /// Deregistered pair by the pair contract address
+    RetiredPair {
+        address: String,
+    },
+    RetiredPairs {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllowedCreators {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    ApprovedTokens {
+        start_after: Option<AssetInfo>,
+        limit: Option<u32>,
+    },
+    NativeTokenDecimals {
+        denom: String,
     },
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
-    pub owner: String,
+    /// None once the ownership is renounced
+    pub owner: Option<String>,
     pub pair_code_id: u64,
     pub token_code_id: u64,
+    pub ownership_proposal: Option<OwnershipProposalResponse>,
+    pub pair_creation_mode: PairCreationMode,
 }
 
-/// We currently take no arguments for migrations
+/// The pairs stored without asset decimals are backfilled by the migration,
+/// the decimals of their native tokens must be registered or given here
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    #[serde(default)]
+    pub native_token_decimals: Vec<NativeTokenDecimals>,
+    /// The stored pairs are migrated in pages in the order of the `pairs` query,
+    /// the migration is repeated from the last pair of a page until none is left
+    pub start_after: Option<[AssetInfo; 2]>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NativeTokenDecimals {
+    pub denom: String,
+    pub decimals: u8,
+}
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PairsResponse {
     pub pairs: Vec<PairInfo>,
+    /// Pools of the pairs in the same order, only with `include_pools`
+    pub pools: Option<Vec<PoolResponse>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairCountResponse {
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairByCreationResponse {
+    /// Registration sequence number, starting from 1
+    pub index: u64,
+    pub pair_info: PairInfo,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairsByCreationResponse {
+    pub pairs: Vec<PairByCreationResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowedCreatorsResponse {
+    pub creators: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovedTokensResponse {
+    pub asset_infos: Vec<AssetInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RetiredPairResponse {
+    pub pair_info: PairInfo,
+    /// Block time in seconds of the deregistration
+    pub retired_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RetiredPairsResponse {
+    pub pairs: Vec<RetiredPairResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NativeTokenDecimalsResponse {
+    pub decimals: u8,
 }
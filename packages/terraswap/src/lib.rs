@@ -1,8 +1,11 @@
 pub mod asset;
 pub mod factory;
+pub mod oracle;
+pub mod ownership;
 pub mod pair;
 pub mod querier;
 pub mod router;
+pub mod routing;
 pub mod token;
 
 #[cfg(test)]
@@ -10,3 +13,9 @@ mod mock_querier;
 
 #[cfg(test)]
 mod testing;
+
+#[cfg(test)]
+mod routing_mock_querier;
+
+#[cfg(test)]
+mod routing_testing;
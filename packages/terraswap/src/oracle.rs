@@ -0,0 +1,21 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset::AssetInfo;
+
+use cosmwasm_std::Decimal;
+
+/// Query interface an oracle contract must expose to price an oracle pair
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Price { base: AssetInfo, quote: AssetInfo },
+}
+
+/// PriceResponse returns the amount of `quote` per unit of `base`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceResponse {
+    pub rate: Decimal,
+    /// Block time in seconds of the last price update
+    pub last_updated: u64,
+}
@@ -0,0 +1,205 @@
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use cosmwasm_std::{CanonicalAddr, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+use cw_storage_plus::Item;
+use thiserror::Error;
+
+/// Upper bound of `expires_in` for an ownership proposal, 14 days in seconds
+pub const MAX_OWNERSHIP_PROPOSAL_TTL: u64 = 14 * 24 * 60 * 60;
+
+/// Pending ownership transfer, the proposed owner has to claim it before `expires_at`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnershipProposalResponse {
+    pub owner: String,
+    /// Block time in seconds
+    pub expires_at: u64,
+}
+
+/// Stored pending ownership transfer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnershipProposal {
+    pub owner: CanonicalAddr,
+    pub expires_at: u64,
+}
+
+/// Config of a contract holding its owner, None once the ownership is renounced
+pub trait Owned {
+    fn owner(&self) -> Option<&CanonicalAddr>;
+    fn set_owner(&mut self, owner: Option<CanonicalAddr>);
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum OwnershipError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("unauthorized")]
+    Unauthorized {},
+
+    #[error("no ownership proposal")]
+    NoOwnershipProposal {},
+
+    #[error("ownership proposal expired")]
+    OwnershipProposalExpired {},
+
+    #[error("expires_in must not exceed {max} seconds")]
+    InvalidExpiresIn { max: u64 },
+}
+
+impl From<OwnershipError> for StdError {
+    fn from(err: OwnershipError) -> Self {
+        match err {
+            OwnershipError::Std(err) => err,
+            err => StdError::generic_err(err.to_string()),
+        }
+    }
+}
+
+pub fn assert_owner<C: Owned>(
+    deps: Deps,
+    config: &C,
+    info: &MessageInfo,
+) -> Result<(), OwnershipError> {
+    match config.owner() {
+        Some(owner) if *owner == deps.api.addr_canonicalize(info.sender.as_str())? => Ok(()),
+        _ => Err(OwnershipError::Unauthorized {}),
+    }
+}
+
+// Only owner can execute it
+pub fn propose_new_owner<C, T>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: &Item<C>,
+    proposal: &Item<OwnershipProposal>,
+    owner: String,
+    expires_in: u64,
+) -> Result<Response<T>, OwnershipError>
+where
+    C: Owned + Serialize + DeserializeOwned,
+    T: Clone + fmt::Debug + PartialEq + JsonSchema,
+{
+    // permission check
+    assert_owner(deps.as_ref(), &config.load(deps.storage)?, &info)?;
+
+    if expires_in > MAX_OWNERSHIP_PROPOSAL_TTL {
+        return Err(OwnershipError::InvalidExpiresIn {
+            max: MAX_OWNERSHIP_PROPOSAL_TTL,
+        });
+    }
+
+    // validate address format
+    let new_owner = deps.api.addr_validate(&owner)?;
+    let expires_at = env.block.time.seconds() + expires_in;
+
+    proposal.save(
+        deps.storage,
+        &OwnershipProposal {
+            owner: deps.api.addr_canonicalize(new_owner.as_str())?,
+            expires_at,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "propose_new_owner"),
+        ("new_owner", new_owner.as_str()),
+        ("expires_at", &expires_at.to_string()),
+    ]))
+}
+
+// Only the proposed owner can execute it
+pub fn claim_ownership<C, T>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: &Item<C>,
+    proposal: &Item<OwnershipProposal>,
+) -> Result<Response<T>, OwnershipError>
+where
+    C: Owned + Serialize + DeserializeOwned,
+    T: Clone + fmt::Debug + PartialEq + JsonSchema,
+{
+    let ownership_proposal: OwnershipProposal = proposal
+        .may_load(deps.storage)?
+        .ok_or(OwnershipError::NoOwnershipProposal {})?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != ownership_proposal.owner {
+        return Err(OwnershipError::Unauthorized {});
+    }
+
+    if env.block.time.seconds() >= ownership_proposal.expires_at {
+        return Err(OwnershipError::OwnershipProposalExpired {});
+    }
+
+    config.update(deps.storage, |mut config| -> StdResult<_> {
+        config.set_owner(Some(ownership_proposal.owner));
+        Ok(config)
+    })?;
+    proposal.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "claim_ownership"),
+        ("new_owner", info.sender.as_str()),
+    ]))
+}
+
+// Only owner can execute it
+pub fn drop_ownership_proposal<C, T>(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: &Item<C>,
+    proposal: &Item<OwnershipProposal>,
+) -> Result<Response<T>, OwnershipError>
+where
+    C: Owned + Serialize + DeserializeOwned,
+    T: Clone + fmt::Debug + PartialEq + JsonSchema,
+{
+    // permission check
+    assert_owner(deps.as_ref(), &config.load(deps.storage)?, &info)?;
+
+    proposal.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_ownership_proposal"))
+}
+
+// Only owner can execute it
+pub fn renounce_ownership<C, T>(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: &Item<C>,
+    proposal: &Item<OwnershipProposal>,
+) -> Result<Response<T>, OwnershipError>
+where
+    C: Owned + Serialize + DeserializeOwned,
+    T: Clone + fmt::Debug + PartialEq + JsonSchema,
+{
+    let mut state: C = config.load(deps.storage)?;
+
+    // permission check
+    assert_owner(deps.as_ref(), &state, &info)?;
+
+    state.set_owner(None);
+    config.save(deps.storage, &state)?;
+    proposal.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "renounce_ownership"))
+}
+
+pub fn query_ownership_proposal(
+    deps: Deps,
+    proposal: &Item<OwnershipProposal>,
+) -> StdResult<Option<OwnershipProposalResponse>> {
+    proposal
+        .may_load(deps.storage)?
+        .map(|proposal| {
+            Ok(OwnershipProposalResponse {
+                owner: deps.api.addr_humanize(&proposal.owner)?.to_string(),
+                expires_at: proposal.expires_at,
+            })
+        })
+        .transpose()
+}
@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::asset::{Asset, AssetInfo};
+use crate::factory::NativeTokenDecimals;
 
 use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
@@ -10,8 +11,29 @@ use cw20::Cw20ReceiveMsg;
 pub struct InstantiateMsg {
     /// Asset infos
     pub asset_infos: [AssetInfo; 2],
+    /// Decimals of the assets in the same order
+    pub asset_decimals: [u8; 2],
     /// Token contract code id for initialization
     pub token_code_id: u64,
+    /// Pricing of the pair, defaults to constant product
+    pub pair_type: Option<PairType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairType {
+    /// x * y = k pricing with a 0.3% commission
+    ConstantProduct {},
+    /// Swaps at the rate of `oracle` (amount of asset 1 per unit of asset 0)
+    /// minus `spread`, plus a fee of up to `skew_fee_rate` when the swap
+    /// leaves the pool unbalanced. Falls back to constant product when the
+    /// oracle price is older than `max_age` seconds
+    Oracle {
+        oracle: String,
+        max_age: u64,
+        spread: Decimal,
+        skew_fee_rate: Decimal,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -31,6 +53,18 @@ pub enum ExecuteMsg {
         max_spread: Option<Decimal>,
         to: Option<String>,
     },
+    /// Internal callback minting LP tokens for the amounts received
+    /// by ProvideLiquidity, only callable by the pair itself
+    ProvideLiquidityCallback {
+        receiver: String,
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Sync sets the reserves to the current balances
+    Sync {},
+    /// Skim sends the balances exceeding the reserves to `to`
+    Skim {
+        to: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -49,15 +83,45 @@ pub enum Cw20HookMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Pair {},
+    PairType {},
     Pool {},
-    Simulation { offer_asset: Asset },
-    ReverseSimulation { ask_asset: Asset },
+    Simulation {
+        offer_asset: Asset,
+    },
+    ReverseSimulation {
+        ask_asset: Asset,
+    },
+    Stats {},
+    DailyStats {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    SpotPrice {
+        base: AssetInfo,
+    },
+    PriceImpact {
+        offer_asset: Asset,
+    },
+    MaxOfferForImpact {
+        offer_asset_info: AssetInfo,
+        max_impact: Decimal,
+    },
+    SimulateProvide {
+        assets: [Asset; 2],
+    },
+    SimulateWithdraw {
+        amount: Uint128,
+    },
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PoolResponse {
+    /// Reserves the pair prices swaps and liquidity with
     pub assets: [Asset; 2],
+    /// Actual balances of the pair, the excess over the reserves
+    /// can be taken with `Skim` or added with `Sync`
+    pub balances: [Asset; 2],
     pub total_share: Uint128,
 }
 
@@ -77,6 +141,92 @@ pub struct ReverseSimulationResponse {
     pub commission_amount: Uint128,
 }
 
-/// We currently take no arguments for migrations
+/// AssetStats holds the cumulative swap statistics of a pool asset
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct AssetStats {
+    pub info: AssetInfo,
+    /// Amount offered to the pool plus amount returned from the pool
+    pub volume: Uint128,
+    /// Commission collected in this asset
+    pub commission: Uint128,
+    /// Number of swaps offering this asset
+    pub swap_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub assets: [AssetStats; 2],
+}
+
+/// DailyStats holds the swap statistics of a single day,
+/// where `day` is the block time in seconds divided by 86400
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DailyStats {
+    pub day: u64,
+    pub assets: [AssetStats; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DailyStatsResponse {
+    pub days: Vec<DailyStats>,
+}
+
+/// SpotPriceResponse returns the reserve ratio of the pool,
+/// `price` is the amount of `quote` per unit of `base`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpotPriceResponse {
+    pub base: AssetInfo,
+    pub quote: AssetInfo,
+    pub price: Decimal,
+}
+
+/// PriceImpactResponse compares the execution price of a swap,
+/// net of commission, with the spot price of the pool
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceImpactResponse {
+    /// Amount of ask asset per unit of offer asset before the swap
+    pub spot_price: Decimal,
+    /// Amount of ask asset received per unit of offer asset
+    pub execution_price: Decimal,
+    /// 1 - execution_price / spot_price
+    pub price_impact: Decimal,
+    pub return_amount: Uint128,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+}
+
+/// MaxOfferForImpactResponse returns the largest offer amount
+/// whose price impact does not exceed the requested `max_impact`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MaxOfferForImpactResponse {
+    pub offer_amount: Uint128,
+    pub return_amount: Uint128,
+    pub price_impact: Decimal,
+}
+
+/// SimulateProvideResponse returns the share minted for a deposit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateProvideResponse {
+    pub share: Uint128,
+    /// Deposit amounts backing the minted share
+    pub used_assets: [Asset; 2],
+    /// Deposit amounts exceeding the pool ratio, these are not
+    /// refunded and are added to the pool for all liquidity providers
+    pub unused_assets: [Asset; 2],
+}
+
+/// SimulateWithdrawResponse returns the assets redeemed for a share amount
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateWithdrawResponse {
+    pub refund_assets: [Asset; 2],
+    /// Refund assets after the native token tax is deducted
+    pub received_assets: [Asset; 2],
+}
+
+/// The pairs created before the decimals registry have no asset decimals,
+/// the migration queries the cw20 decimals and takes the native ones from here
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    #[serde(default)]
+    pub native_token_decimals: Vec<NativeTokenDecimals>,
+}
@@ -1,12 +1,18 @@
 use crate::asset::{Asset, AssetInfo, PairInfo};
 use crate::factory::QueryMsg as FactoryQueryMsg;
-use crate::pair::{QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse};
+use crate::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
+use crate::pair::{
+    PoolResponse, QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse,
+};
 
 use cosmwasm_std::{
-    to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, QuerierWrapper,
+    to_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, Decimal, QuerierWrapper,
     QueryRequest, StdResult, Uint128, WasmQuery,
 };
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use terra_cosmwasm::TerraQuerier;
+
+static DECIMAL_FRACTION: Uint128 = Uint128::new(1_000_000_000_000_000_000u128);
 
 pub fn query_balance(
     querier: &QuerierWrapper,
@@ -56,6 +62,16 @@ pub fn query_supply(querier: &QuerierWrapper, contract_addr: Addr) -> StdResult<
     Ok(token_info.total_supply)
 }
 
+pub fn query_token_info(
+    querier: &QuerierWrapper,
+    contract_addr: Addr,
+) -> StdResult<TokenInfoResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: contract_addr.to_string(),
+        msg: to_binary(&Cw20QueryMsg::TokenInfo {})?,
+    }))
+}
+
 pub fn query_pair_info(
     querier: &QuerierWrapper,
     factory_contract: Addr,
@@ -69,6 +85,13 @@ pub fn query_pair_info(
     }))
 }
 
+pub fn query_pool(querier: &QuerierWrapper, pair_contract: Addr) -> StdResult<PoolResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair_contract.to_string(),
+        msg: to_binary(&PairQueryMsg::Pool {})?,
+    }))
+}
+
 pub fn simulate(
     querier: &QuerierWrapper,
     pair_contract: Addr,
@@ -94,3 +117,56 @@ pub fn reverse_simulate(
         })?,
     }))
 }
+
+pub fn query_oracle_price(
+    querier: &QuerierWrapper,
+    oracle_contract: Addr,
+    base: &AssetInfo,
+    quote: &AssetInfo,
+) -> StdResult<PriceResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: oracle_contract.to_string(),
+        msg: to_binary(&OracleQueryMsg::Price {
+            base: base.clone(),
+            quote: quote.clone(),
+        })?,
+    }))
+}
+
+/// Tax included in the amount, the rest of the amount can be sent with the tax
+pub fn compute_tax(querier: &QuerierWrapper, amount: Uint128, denom: String) -> StdResult<Uint128> {
+    if denom == "uluna" {
+        return Ok(Uint128::zero());
+    }
+
+    let terra_querier = TerraQuerier::new(querier);
+    let tax_rate: Decimal = (terra_querier.query_tax_rate()?).rate;
+    let tax_cap: Uint128 = (terra_querier.query_tax_cap(denom)?).cap;
+    Ok(std::cmp::min(
+        amount.checked_sub(amount.multiply_ratio(
+            DECIMAL_FRACTION,
+            DECIMAL_FRACTION * tax_rate + DECIMAL_FRACTION,
+        ))?,
+        tax_cap,
+    ))
+}
+
+/// Tax to add to the amount, so the amount remains after the tax is deducted.
+/// Rounded up, as compute_tax rounds the remaining amount down
+pub fn compute_reverse_tax(
+    querier: &QuerierWrapper,
+    amount: Uint128,
+    denom: String,
+) -> StdResult<Uint128> {
+    if denom == "uluna" {
+        return Ok(Uint128::zero());
+    }
+
+    let terra_querier = TerraQuerier::new(querier);
+    let tax_rate: Decimal = (terra_querier.query_tax_rate()?).rate;
+    let tax_cap: Uint128 = (terra_querier.query_tax_cap(denom)?).cap;
+    Ok(std::cmp::min(
+        amount.checked_sub(amount * (Decimal::one() - tax_rate))?,
+        tax_cap,
+    ))
+}
@@ -1,16 +1,26 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Uint128};
+use cosmwasm_std::{Decimal, StdResult, Uint128};
 use cw20::Cw20ReceiveMsg;
 
-use crate::asset::AssetInfo;
+use crate::asset::{Asset, AssetInfo};
+use crate::ownership::OwnershipProposalResponse;
+
+/// Protocol of the pools speaking the terraswap pair interface, which need no adapter
+pub const TERRASWAP_PROTOCOL: &str = "terraswap";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub terraswap_factory: String,
 }
 
+/// The routers stored before the owner have none, the migration sets it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    pub owner: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SwapOperation {
@@ -21,6 +31,23 @@ pub enum SwapOperation {
     TerraSwap {
         offer_asset_info: AssetInfo,
         ask_asset_info: AssetInfo,
+        /// Passed to the pair swap, overrides the max_spread of ExecuteSwapOperations
+        max_spread: Option<Decimal>,
+        /// Passed to the pair swap
+        belief_price: Option<Decimal>,
+    },
+    /// Swap on a pair contract outside the factory, the owner has to allow the pool first
+    Pool {
+        pair_address: String,
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+        /// `terraswap` for a pair speaking the terraswap pair interface,
+        /// otherwise the protocol of the adapter translating the swap for the pair
+        protocol: String,
+        /// Passed to the pair swap, overrides the max_spread of ExecuteSwapOperations
+        max_spread: Option<Decimal>,
+        /// Passed to the pair swap
+        belief_price: Option<Decimal>,
     },
     // todo: maybe take these out from SwapOperation and put them in a BridgeOperation enum
     // TerraBridge uses memos, and smart contracts cannot handle memos.
@@ -50,17 +77,67 @@ pub enum SwapOperation {
 }
 
 impl SwapOperation {
-    pub fn get_target_asset_info(&self) -> AssetInfo {
+    pub fn get_offer_asset_info(&self) -> StdResult<AssetInfo> {
+        match self {
+            SwapOperation::NativeSwap { offer_denom, .. } => Ok(AssetInfo::NativeToken {
+                denom: offer_denom.clone(),
+            }),
+            SwapOperation::TerraSwap {
+                offer_asset_info, ..
+            }
+            | SwapOperation::Pool {
+                offer_asset_info, ..
+            } => Ok(offer_asset_info.clone()),
+            SwapOperation::WormHoleBridge { asset_info, .. }
+            | SwapOperation::IbcTransfer { asset_info, .. } => Ok(asset_info.clone()),
+        }
+    }
+
+    /// A bridge moves its asset to another chain, so it targets the asset it offers
+    pub fn get_target_asset_info(&self) -> StdResult<AssetInfo> {
         match self {
-            SwapOperation::NativeSwap { ask_denom, .. } => AssetInfo::NativeToken {
+            SwapOperation::NativeSwap { ask_denom, .. } => Ok(AssetInfo::NativeToken {
                 denom: ask_denom.clone(),
-            },
-            SwapOperation::TerraSwap { ask_asset_info, .. } => ask_asset_info.clone(),
-            _ => panic!("not implemented")
+            }),
+            SwapOperation::TerraSwap { ask_asset_info, .. }
+            | SwapOperation::Pool { ask_asset_info, .. } => Ok(ask_asset_info.clone()),
+            SwapOperation::WormHoleBridge { asset_info, .. }
+            | SwapOperation::IbcTransfer { asset_info, .. } => Ok(asset_info.clone()),
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapInput {
+    pub offer_asset: Asset,
+    /// Starts with the offer asset and ends with the ask asset shared by all the inputs
+    pub operations: Vec<SwapOperation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DistributionLeg {
+    /// Starts with the offer asset shared by all the legs
+    pub operations: Vec<SwapOperation>,
+    pub recipient: String,
+    /// Ask asset amount the recipient must receive,
+    /// the offer amount of the leg is reverse simulated from it
+    pub minimum_receive: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowedPool {
+    pub pair_address: String,
+    /// `terraswap` or the protocol of a configured adapter
+    pub protocol: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProtocolAdapter {
+    pub protocol: String,
+    /// Contract accepting the adapter messages for the pools of the protocol
+    pub adapter: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -70,13 +147,67 @@ pub enum ExecuteMsg {
         operations: Vec<SwapOperation>,
         minimum_receive: Option<Uint128>,
         to: Option<String>,
+        /// Passed to every TerraSwap and Pool operation without its own max_spread
+        max_spread: Option<Decimal>,
+    },
+
+    /// Execute multiple BuyOperation returning the ask_amount,
+    /// the offer not spent is refunded to the sender
+    ExecuteSwapOperationsExactOut {
+        operations: Vec<SwapOperation>,
+        ask_amount: Uint128,
+        max_offer: Uint128,
+        to: Option<String>,
+    },
+    /// Swap several offer assets into the same ask asset,
+    /// native offers are sent along and tokens are taken with TransferFrom
+    ExecuteMultiSwap {
+        inputs: Vec<SwapInput>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    },
+    /// Swap one offer into the minimum_receive of every leg,
+    /// the offer not spent is refunded to the sender
+    ExecuteDistribution {
+        legs: Vec<DistributionLeg>,
+    },
+    /// Divide the offer amount between the routes by their fraction
+    /// and execute each route like ExecuteSwapOperations
+    ExecuteSplitSwap {
+        routes: Vec<(Decimal, Vec<SwapOperation>)>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    },
+    /// UpdateAllowedPools adds and removes the pools Pool operations can swap on
+    UpdateAllowedPools {
+        add: Vec<AllowedPool>,
+        remove: Vec<String>,
+    },
+    /// UpdateAdapters sets and removes the adapters of the protocols
+    UpdateAdapters {
+        add: Vec<ProtocolAdapter>,
+        remove: Vec<String>,
     },
+    /// ProposeNewOwner creates a proposal to hand over the ownership,
+    /// which has to be claimed by the new owner within `expires_in` seconds
+    ProposeNewOwner {
+        owner: String,
+        expires_in: u64,
+    },
+    /// ClaimOwnership accepts the pending ownership proposal
+    ClaimOwnership {},
+    /// DropOwnershipProposal removes the pending ownership proposal
+    DropOwnershipProposal {},
+    /// RenounceOwnership removes the owner, leaving the allowed pools and adapters immutable
+    RenounceOwnership {},
 
     /// Internal use
-    /// Swap all offer tokens to ask token
+    /// Swap all offer tokens to ask token,
+    /// or only offer_amount when it is given
     ExecuteSwapOperation {
         operation: SwapOperation,
         to: Option<String>,
+        offer_amount: Option<Uint128>,
     },
     /// Internal use
     /// Check the swap amount is exceed minimum_receive
@@ -114,6 +245,21 @@ pub enum Cw20HookMsg {
         operations: Vec<SwapOperation>,
         minimum_receive: Option<Uint128>,
         to: Option<String>,
+        max_spread: Option<Decimal>,
+    },
+    ExecuteSwapOperationsExactOut {
+        operations: Vec<SwapOperation>,
+        ask_amount: Uint128,
+        max_offer: Uint128,
+        to: Option<String>,
+    },
+    ExecuteDistribution {
+        legs: Vec<DistributionLeg>,
+    },
+    ExecuteSplitSwap {
+        routes: Vec<(Decimal, Vec<SwapOperation>)>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
     },
     /// Execute multiple swaps and bridges
     ExecuteTeleport {
@@ -133,16 +279,148 @@ pub enum QueryMsg {
         offer_amount: Uint128,
         operations: Vec<SwapOperation>,
     },
+    /// Walks the operations backwards to find the offer amount returning the ask_amount
+    ReverseSimulateSwapOperations {
+        ask_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
+    /// Finds the operations returning the most ask asset,
+    /// going through the registered pairs and the native swaps
+    FindBestRoute {
+        offer_asset: Asset,
+        ask_asset_info: AssetInfo,
+        /// Defaults to and is capped at 3
+        max_hops: Option<u32>,
+    },
+    SimulateSplitSwap {
+        offer_amount: Uint128,
+        routes: Vec<(Decimal, Vec<SwapOperation>)>,
+    },
+    AllowedPools {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Adapters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub terraswap_factory: String,
+    /// None once the ownership is renounced
+    pub owner: Option<String>,
+    pub ownership_proposal: Option<OwnershipProposalResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowedPoolsResponse {
+    pub pools: Vec<AllowedPool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdaptersResponse {
+    pub adapters: Vec<ProtocolAdapter>,
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct SimulateSwapOperationsResponse {
     pub amount: Uint128,
+    /// Breakdown of each operation, in the order of the operations
+    pub hops: Vec<SwapOperationSimulation>,
+    /// Share of the offer amount, swapped at the spot price of every operation,
+    /// lost to commission, spread and tax
+    pub price_impact: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapOperationSimulation {
+    /// Pair of a TerraSwap or Pool operation, none for a market swap
+    pub pair_contract: Option<String>,
+    /// Amount reaching the operation, before the offer tax
+    pub offer_amount: Uint128,
+    /// Amount passed on to the next operation, after the return tax
+    pub return_amount: Uint128,
+    pub commission_amount: Uint128,
+    pub spread_amount: Uint128,
+    /// Tax deducted from the offer amount, in the offer asset
+    pub offer_tax_amount: Uint128,
+    /// Tax deducted from the return amount, in the ask asset
+    pub return_tax_amount: Uint128,
+    /// Ask asset per unit of offer asset
+    pub spot_price: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReverseSimulateSwapOperationsResponse {
+    pub offer_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FindBestRouteResponse {
+    pub operations: Vec<SwapOperation>,
+    /// Expected ask asset amount
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateSplitSwapResponse {
+    /// Ask asset amount of each route, in the order of the routes
+    pub amounts: Vec<Uint128>,
+    /// Total ask asset amount
+    pub amount: Uint128,
+}
+
+/// Messages of an adapter swapping on the pools of another protocol,
+/// the offer is sent along like a swap on a terraswap pair
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdapterExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Swap the sent native offer, the return goes to `to` or back to the sender
+    Swap {
+        pair_address: String,
+        offer_asset: Asset,
+        ask_asset_info: AssetInfo,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdapterCw20HookMsg {
+    /// Swap the received token, the return goes to `to` or back to the sender
+    Swap {
+        pair_address: String,
+        ask_asset_info: AssetInfo,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+/// Queries of an adapter, answered with the responses of the terraswap pair queries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdapterQueryMsg {
+    Simulation {
+        pair_address: String,
+        offer_asset: Asset,
+        ask_asset_info: AssetInfo,
+    },
+    ReverseSimulation {
+        pair_address: String,
+        ask_asset: Asset,
+        offer_asset_info: AssetInfo,
+    },
+    SpotPrice {
+        pair_address: String,
+        base: AssetInfo,
+        quote: AssetInfo,
+    },
 }
@@ -0,0 +1,979 @@
+use cosmwasm_std::{
+    to_binary, Addr, Api, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, QueryRequest,
+    Response, StdError, StdResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw20::Cw20ExecuteMsg;
+use serde::Deserialize;
+use terra_cosmwasm::{
+    create_swap_msg, create_swap_send_msg, SwapResponse, TerraMsgWrapper, TerraQuerier,
+};
+
+use crate::asset::{Asset, AssetInfo, PairInfo};
+use crate::pair::{
+    ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg, ReverseSimulationResponse,
+    SimulationResponse, SpotPriceResponse,
+};
+use crate::querier::{
+    compute_reverse_tax, compute_tax, query_balance, query_pair_info, query_token_balance,
+};
+use crate::router::{
+    AdapterCw20HookMsg, AdapterExecuteMsg, AdapterQueryMsg, ExecuteMsg,
+    ReverseSimulateSwapOperationsResponse, SimulateSwapOperationsResponse, SwapOperation,
+    SwapOperationSimulation,
+};
+
+/// What a router adds on top of the shared swap operations,
+/// the pools it allows and the bridges it supports
+pub trait RouterExtension {
+    /// Factory the pairs of the TerraSwap operations are looked up in
+    fn terraswap_factory(&self, deps: Deps) -> StdResult<Addr>;
+
+    /// Pair of a Pool operation
+    fn pool_pair(
+        &self,
+        _deps: Deps,
+        _pair_address: &str,
+        _protocol: &str,
+    ) -> StdResult<PairContract> {
+        Err(StdError::generic_err("not implemented"))
+    }
+
+    /// Messages executing a bridge operation, which moves the router balance of its asset
+    fn bridge_msgs(
+        &self,
+        _deps: Deps,
+        _env: &Env,
+        _operation: SwapOperation,
+        _to: Option<String>,
+    ) -> StdResult<Vec<CosmosMsg<TerraMsgWrapper>>> {
+        Err(StdError::generic_err("given operation is not implemented"))
+    }
+}
+
+/// Pair contract a swap operation goes through,
+/// either directly or through the adapter of its protocol
+pub struct PairContract {
+    pub address: Addr,
+    pub adapter: Option<Addr>,
+}
+
+impl PairContract {
+    /// Pair speaking the terraswap pair interface
+    pub fn terraswap(address: Addr) -> Self {
+        PairContract {
+            address,
+            adapter: None,
+        }
+    }
+
+    pub fn swap_msg(
+        &self,
+        deps: Deps,
+        offer_asset: Asset,
+        ask_asset_info: AssetInfo,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
+        to: Option<String>,
+    ) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+        let adapter = match &self.adapter {
+            Some(adapter) => adapter,
+            None => {
+                return asset_into_swap_msg(
+                    deps,
+                    self.address.clone(),
+                    offer_asset,
+                    max_spread,
+                    belief_price,
+                    to,
+                )
+            }
+        };
+
+        match offer_asset.info.clone() {
+            AssetInfo::NativeToken { denom } => {
+                // deduct tax first
+                let amount = offer_asset.amount.checked_sub(compute_tax(
+                    &deps.querier,
+                    offer_asset.amount,
+                    denom.clone(),
+                )?)?;
+
+                Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: adapter.to_string(),
+                    funds: vec![Coin { denom, amount }],
+                    msg: to_binary(&AdapterExecuteMsg::Swap {
+                        pair_address: self.address.to_string(),
+                        offer_asset: Asset {
+                            amount,
+                            ..offer_asset
+                        },
+                        ask_asset_info,
+                        max_spread,
+                        belief_price,
+                        to,
+                    })?,
+                }))
+            }
+            AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: adapter.to_string(),
+                    amount: offer_asset.amount,
+                    msg: to_binary(&AdapterCw20HookMsg::Swap {
+                        pair_address: self.address.to_string(),
+                        ask_asset_info,
+                        max_spread,
+                        belief_price,
+                        to,
+                    })?,
+                })?,
+            })),
+        }
+    }
+
+    pub fn simulate(
+        &self,
+        deps: Deps,
+        offer_asset: Asset,
+        ask_asset_info: AssetInfo,
+    ) -> StdResult<SimulationResponse> {
+        let (contract_addr, msg) = match &self.adapter {
+            Some(adapter) => (
+                adapter.to_string(),
+                to_binary(&AdapterQueryMsg::Simulation {
+                    pair_address: self.address.to_string(),
+                    offer_asset,
+                    ask_asset_info,
+                })?,
+            ),
+            None => (
+                self.address.to_string(),
+                to_binary(&PairQueryMsg::Simulation { offer_asset })?,
+            ),
+        };
+
+        deps.querier
+            .query(&QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }))
+    }
+
+    pub fn reverse_simulate(
+        &self,
+        deps: Deps,
+        ask_asset: Asset,
+        offer_asset_info: AssetInfo,
+    ) -> StdResult<ReverseSimulationResponse> {
+        let (contract_addr, msg) = match &self.adapter {
+            Some(adapter) => (
+                adapter.to_string(),
+                to_binary(&AdapterQueryMsg::ReverseSimulation {
+                    pair_address: self.address.to_string(),
+                    ask_asset,
+                    offer_asset_info,
+                })?,
+            ),
+            None => (
+                self.address.to_string(),
+                to_binary(&PairQueryMsg::ReverseSimulation { ask_asset })?,
+            ),
+        };
+
+        deps.querier
+            .query(&QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }))
+    }
+
+    /// Ask asset per unit of offer asset
+    pub fn spot_price(
+        &self,
+        deps: Deps,
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    ) -> StdResult<Decimal> {
+        let (contract_addr, msg) = match &self.adapter {
+            Some(adapter) => (
+                adapter.to_string(),
+                to_binary(&AdapterQueryMsg::SpotPrice {
+                    pair_address: self.address.to_string(),
+                    base: offer_asset_info.clone(),
+                    quote: ask_asset_info.clone(),
+                })?,
+            ),
+            None => (
+                self.address.to_string(),
+                to_binary(&PairQueryMsg::SpotPrice {
+                    base: offer_asset_info.clone(),
+                })?,
+            ),
+        };
+
+        let res: StdResult<SpotPriceResponse> = deps
+            .querier
+            .query(&QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }));
+        match res {
+            Ok(res) => Ok(res.price),
+            // pairs deployed before the spot price query are priced by their reserves
+            Err(_) if self.adapter.is_none() => {
+                self.reserves_price(deps, &offer_asset_info, &ask_asset_info)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Ask reserve per unit of offer reserve
+    fn reserves_price(
+        &self,
+        deps: Deps,
+        offer_asset_info: &AssetInfo,
+        ask_asset_info: &AssetInfo,
+    ) -> StdResult<Decimal> {
+        let res: PoolReserves = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.address.to_string(),
+            msg: to_binary(&PairQueryMsg::Pool {})?,
+        }))?;
+
+        let reserve = |asset_info: &AssetInfo| -> StdResult<Uint128> {
+            res.assets
+                .iter()
+                .find(|asset| asset.info.equal(asset_info))
+                .map(|asset| asset.amount)
+                .ok_or_else(|| {
+                    StdError::generic_err(format!("asset is not in the pair: {}", asset_info))
+                })
+        };
+        let offer_reserve = reserve(offer_asset_info)?;
+        let ask_reserve = reserve(ask_asset_info)?;
+        if offer_reserve.is_zero() {
+            return Err(StdError::generic_err("the pair has no liquidity"));
+        }
+
+        Ok(Decimal::from_ratio(ask_reserve, offer_reserve))
+    }
+}
+
+/// Reserves of a pool response, which the pairs deployed
+/// before the spot price query answer without the balances
+#[derive(Deserialize)]
+struct PoolReserves {
+    assets: [Asset; 2],
+}
+
+pub fn execute_swap_operations(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    to: Option<Addr>,
+    max_spread: Option<Decimal>,
+) -> StdResult<Response<TerraMsgWrapper>> {
+    if operations.is_empty() {
+        return Err(StdError::generic_err("must provide operations"));
+    }
+
+    // Assert the operations are properly set
+    assert_operations(&operations)?;
+
+    let to = if let Some(to) = to { to } else { sender };
+    let target_asset_info = operations.last().unwrap().get_target_asset_info()?;
+
+    let operations = operations
+        .into_iter()
+        .map(|operation| with_default_max_spread(operation, max_spread))
+        .collect();
+    let mut messages = swap_operation_msgs(&env, operations, None, Some(&to))?;
+
+    // Execute minimum amount assertion
+    if let Some(minimum_receive) = minimum_receive {
+        messages.push(minimum_receive_msg(
+            deps.as_ref(),
+            &env,
+            target_asset_info,
+            minimum_receive,
+            &to,
+        )?);
+    }
+
+    Ok(Response::new().add_messages(messages))
+}
+
+/// Messages executing the operations one after another, the first operation swaps
+/// the offer_amount when it is given and the last one sends its return to `to`,
+/// the return stays in the router without it
+pub fn swap_operation_msgs(
+    env: &Env,
+    operations: Vec<SwapOperation>,
+    offer_amount: Option<Uint128>,
+    to: Option<&Addr>,
+) -> StdResult<Vec<CosmosMsg<TerraMsgWrapper>>> {
+    let operations_len = operations.len();
+    operations
+        .into_iter()
+        .enumerate()
+        .map(|(operation_index, operation)| {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation,
+                    to: if operation_index + 1 == operations_len {
+                        to.map(|to| to.to_string())
+                    } else {
+                        None
+                    },
+                    offer_amount: if operation_index == 0 {
+                        offer_amount
+                    } else {
+                        None
+                    },
+                })?,
+            }))
+        })
+        .collect()
+}
+
+/// Asserts the receiver balance of the asset grows by minimum_receive at least
+pub fn minimum_receive_msg(
+    deps: Deps,
+    env: &Env,
+    asset_info: AssetInfo,
+    minimum_receive: Uint128,
+    receiver: &Addr,
+) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+    let receiver_balance = asset_info.query_pool(&deps.querier, deps.api, receiver.clone())?;
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        funds: vec![],
+        msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+            asset_info,
+            prev_balance: receiver_balance,
+            minimum_receive,
+            receiver: receiver.to_string(),
+        })?,
+    }))
+}
+
+/// Sets the max_spread of a TerraSwap or Pool operation without its own
+pub fn with_default_max_spread(
+    operation: SwapOperation,
+    default_max_spread: Option<Decimal>,
+) -> SwapOperation {
+    match operation {
+        SwapOperation::TerraSwap {
+            offer_asset_info,
+            ask_asset_info,
+            max_spread,
+            belief_price,
+        } => SwapOperation::TerraSwap {
+            offer_asset_info,
+            ask_asset_info,
+            max_spread: max_spread.or(default_max_spread),
+            belief_price,
+        },
+        SwapOperation::Pool {
+            pair_address,
+            offer_asset_info,
+            ask_asset_info,
+            protocol,
+            max_spread,
+            belief_price,
+        } => SwapOperation::Pool {
+            pair_address,
+            offer_asset_info,
+            ask_asset_info,
+            protocol,
+            max_spread: max_spread.or(default_max_spread),
+            belief_price,
+        },
+        _ => operation,
+    }
+}
+
+pub fn assert_minimum_receive(
+    deps: Deps,
+    asset_info: AssetInfo,
+    prev_balance: Uint128,
+    minium_receive: Uint128,
+    receiver: Addr,
+) -> StdResult<Response<TerraMsgWrapper>> {
+    let receiver_balance = asset_info.query_pool(&deps.querier, deps.api, receiver)?;
+    let swap_amount = receiver_balance.checked_sub(prev_balance)?;
+
+    if swap_amount < minium_receive {
+        return Err(StdError::generic_err(format!(
+            "assertion failed; minimum receive amount: {}, swap amount: {}",
+            minium_receive, swap_amount
+        )));
+    }
+
+    Ok(Response::default())
+}
+
+pub fn optional_addr_validate(api: &dyn Api, addr: Option<String>) -> StdResult<Option<Addr>> {
+    let addr = if let Some(addr) = addr {
+        Some(api.addr_validate(&addr)?)
+    } else {
+        None
+    };
+
+    Ok(addr)
+}
+
+/// Asserts every operation offers the ask asset of the operation before it,
+/// and no operation swaps back into an asset the route went through
+pub fn assert_operations(operations: &[SwapOperation]) -> StdResult<()> {
+    if operations.is_empty() {
+        return Err(StdError::generic_err("must provide operations"));
+    }
+
+    // the offer asset of the route followed by the ask asset of every operation
+    let mut asset_infos: Vec<AssetInfo> = vec![operations[0].get_offer_asset_info()?];
+    for (index, operation) in operations.iter().enumerate() {
+        if let SwapOperation::WormHoleBridge { .. } | SwapOperation::IbcTransfer { .. } = operation
+        {
+            return Err(StdError::generic_err(format!(
+                "invalid operations; operation {} is not a swap",
+                index
+            )));
+        }
+
+        if operation.get_offer_asset_info()? != asset_infos[index] {
+            return Err(StdError::generic_err(format!(
+                "invalid operations; operation {} does not offer the ask asset of operation {}",
+                index,
+                index - 1
+            )));
+        }
+
+        let ask_asset_info = operation.get_target_asset_info()?;
+        if asset_infos.contains(&ask_asset_info) {
+            return Err(StdError::generic_err(format!(
+                "invalid operations; operation {} swaps back into {}",
+                index, ask_asset_info
+            )));
+        }
+        asset_infos.push(ask_asset_info);
+    }
+
+    Ok(())
+}
+
+/// Execute swap operation
+/// swap all offer asset to ask asset, or only the offer_amount when it is given
+pub fn execute_swap_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    router: &dyn RouterExtension,
+    operation: SwapOperation,
+    to: Option<String>,
+    offer_amount: Option<Uint128>,
+) -> StdResult<Response<TerraMsgWrapper>> {
+    if env.contract.address != info.sender {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let messages: Vec<CosmosMsg<TerraMsgWrapper>> = match operation {
+        SwapOperation::NativeSwap {
+            offer_denom,
+            ask_denom,
+        } => {
+            let amount = match offer_amount {
+                Some(amount) => amount,
+                None => {
+                    query_balance(&deps.querier, env.contract.address, offer_denom.to_string())?
+                }
+            };
+            if let Some(to) = to {
+                // if the operation is last, and requires send
+                // deduct tax from the offer_coin
+                let amount =
+                    amount.checked_sub(compute_tax(&deps.querier, amount, offer_denom.clone())?)?;
+                vec![create_swap_send_msg(
+                    to,
+                    Coin {
+                        denom: offer_denom,
+                        amount,
+                    },
+                    ask_denom,
+                )]
+            } else {
+                vec![create_swap_msg(
+                    Coin {
+                        denom: offer_denom,
+                        amount,
+                    },
+                    ask_denom,
+                )]
+            }
+        }
+        SwapOperation::TerraSwap {
+            offer_asset_info,
+            ask_asset_info,
+            max_spread,
+            belief_price,
+        } => {
+            let pair_info: PairInfo = query_pair_info(
+                &deps.querier,
+                router.terraswap_factory(deps.as_ref())?,
+                &[offer_asset_info.clone(), ask_asset_info],
+            )?;
+
+            let offer_asset: Asset = Asset {
+                amount: offer_balance(deps.as_ref(), &env, &offer_asset_info, offer_amount)?,
+                info: offer_asset_info,
+            };
+
+            vec![asset_into_swap_msg(
+                deps.as_ref(),
+                Addr::unchecked(pair_info.contract_addr),
+                offer_asset,
+                max_spread,
+                belief_price,
+                to,
+            )?]
+        }
+        SwapOperation::Pool {
+            pair_address,
+            offer_asset_info,
+            ask_asset_info,
+            protocol,
+            max_spread,
+            belief_price,
+        } => {
+            let pair = router.pool_pair(deps.as_ref(), &pair_address, &protocol)?;
+            let offer_asset: Asset = Asset {
+                amount: offer_balance(deps.as_ref(), &env, &offer_asset_info, offer_amount)?,
+                info: offer_asset_info,
+            };
+
+            vec![pair.swap_msg(
+                deps.as_ref(),
+                offer_asset,
+                ask_asset_info,
+                max_spread,
+                belief_price,
+                to,
+            )?]
+        }
+        SwapOperation::WormHoleBridge { .. } | SwapOperation::IbcTransfer { .. } => {
+            router.bridge_msgs(deps.as_ref(), &env, operation, to)?
+        }
+    };
+
+    Ok(Response::new().add_messages(messages))
+}
+
+/// The offer_amount when it is given, otherwise the whole balance of the offer asset
+fn offer_balance(
+    deps: Deps,
+    env: &Env,
+    offer_asset_info: &AssetInfo,
+    offer_amount: Option<Uint128>,
+) -> StdResult<Uint128> {
+    match (offer_amount, offer_asset_info) {
+        (Some(amount), _) => Ok(amount),
+        (None, AssetInfo::NativeToken { denom }) => query_balance(
+            &deps.querier,
+            env.contract.address.clone(),
+            denom.to_string(),
+        ),
+        (None, AssetInfo::Token { contract_addr }) => query_token_balance(
+            &deps.querier,
+            deps.api.addr_validate(contract_addr.as_str())?,
+            env.contract.address.clone(),
+        ),
+    }
+}
+
+pub fn asset_into_swap_msg(
+    deps: Deps,
+    pair_contract: Addr,
+    offer_asset: Asset,
+    max_spread: Option<Decimal>,
+    belief_price: Option<Decimal>,
+    to: Option<String>,
+) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+    match offer_asset.info.clone() {
+        AssetInfo::NativeToken { denom } => {
+            // deduct tax first
+            let amount = offer_asset.amount.checked_sub(compute_tax(
+                &deps.querier,
+                offer_asset.amount,
+                denom.clone(),
+            )?)?;
+
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: pair_contract.to_string(),
+                funds: vec![Coin { denom, amount }],
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        amount,
+                        ..offer_asset
+                    },
+                    belief_price,
+                    max_spread,
+                    to,
+                })?,
+            }))
+        }
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: pair_contract.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset,
+                    belief_price,
+                    max_spread,
+                    to,
+                })?,
+            })?,
+        })),
+    }
+}
+
+pub fn simulate_swap_operations(
+    deps: Deps,
+    router: &dyn RouterExtension,
+    offer_amount: Uint128,
+    operations: Vec<SwapOperation>,
+) -> StdResult<SimulateSwapOperationsResponse> {
+    let terraswap_factory = router.terraswap_factory(deps)?;
+    let terra_querier = TerraQuerier::new(&deps.querier);
+
+    let operations_len = operations.len();
+    if operations_len == 0 {
+        return Err(StdError::generic_err("must provide operations"));
+    }
+
+    let mut operation_index = 0;
+    let mut offer_amount = offer_amount;
+    // The offer amount swapped at the spot price of every operation
+    let mut spot_amount = offer_amount;
+    let mut hops: Vec<SwapOperationSimulation> = vec![];
+    for operation in operations.into_iter() {
+        operation_index += 1;
+
+        let hop = match operation {
+            SwapOperation::NativeSwap {
+                offer_denom,
+                ask_denom,
+            } => {
+                let hop_offer_amount = offer_amount;
+
+                // Deduct tax before query simulation
+                // because last swap is swap_send
+                let mut offer_tax_amount = Uint128::zero();
+                if operation_index == operations_len {
+                    offer_tax_amount =
+                        compute_tax(&deps.querier, offer_amount, offer_denom.clone())?;
+                    offer_amount = offer_amount.checked_sub(offer_tax_amount)?;
+                }
+
+                let spot_price = query_market_spot_price(
+                    &terra_querier,
+                    offer_denom.clone(),
+                    ask_denom.clone(),
+                )?;
+
+                let res: SwapResponse = terra_querier.query_swap(
+                    Coin {
+                        denom: offer_denom,
+                        amount: offer_amount,
+                    },
+                    ask_denom,
+                )?;
+
+                // The market takes no commission, its spread is the loss against the spot price
+                let spread_amount = (offer_amount * spot_price)
+                    .checked_sub(res.receive.amount)
+                    .unwrap_or_else(|_| Uint128::zero());
+
+                offer_amount = res.receive.amount;
+                SwapOperationSimulation {
+                    pair_contract: None,
+                    offer_amount: hop_offer_amount,
+                    return_amount: offer_amount,
+                    commission_amount: Uint128::zero(),
+                    spread_amount,
+                    offer_tax_amount,
+                    return_tax_amount: Uint128::zero(),
+                    spot_price,
+                }
+            }
+            SwapOperation::TerraSwap {
+                offer_asset_info,
+                ask_asset_info,
+                ..
+            } => {
+                let pair_info: PairInfo = query_pair_info(
+                    &deps.querier,
+                    terraswap_factory.clone(),
+                    &[offer_asset_info.clone(), ask_asset_info.clone()],
+                )?;
+
+                let hop = simulate_pair_swap(
+                    deps,
+                    &PairContract::terraswap(Addr::unchecked(pair_info.contract_addr)),
+                    offer_asset_info,
+                    ask_asset_info,
+                    offer_amount,
+                )?;
+                offer_amount = hop.return_amount;
+                hop
+            }
+            SwapOperation::Pool {
+                pair_address,
+                offer_asset_info,
+                ask_asset_info,
+                protocol,
+                ..
+            } => {
+                let pair = router.pool_pair(deps, &pair_address, &protocol)?;
+
+                let hop = simulate_pair_swap(
+                    deps,
+                    &pair,
+                    offer_asset_info,
+                    ask_asset_info,
+                    offer_amount,
+                )?;
+                offer_amount = hop.return_amount;
+                hop
+            }
+            _ => return Err(StdError::generic_err("not implemented")),
+        };
+
+        spot_amount = spot_amount * hop.spot_price;
+        hops.push(hop);
+    }
+
+    let price_impact = if spot_amount > offer_amount {
+        Decimal::from_ratio(spot_amount - offer_amount, spot_amount)
+    } else {
+        Decimal::zero()
+    };
+
+    Ok(SimulateSwapOperationsResponse {
+        amount: offer_amount,
+        hops,
+        price_impact,
+    })
+}
+
+/// Swap on a pair, deducting the tax of sending the offer before it and the return after it
+fn simulate_pair_swap(
+    deps: Deps,
+    pair: &PairContract,
+    offer_asset_info: AssetInfo,
+    ask_asset_info: AssetInfo,
+    offer_amount: Uint128,
+) -> StdResult<SwapOperationSimulation> {
+    // Deduct tax before querying simulation
+    let mut offer_tax_amount = Uint128::zero();
+    if let AssetInfo::NativeToken { denom } = offer_asset_info.clone() {
+        offer_tax_amount = compute_tax(&deps.querier, offer_amount, denom)?;
+    }
+
+    let spot_price = pair.spot_price(deps, offer_asset_info.clone(), ask_asset_info.clone())?;
+    let mut res = pair.simulate(
+        deps,
+        Asset {
+            info: offer_asset_info,
+            amount: offer_amount.checked_sub(offer_tax_amount)?,
+        },
+        ask_asset_info.clone(),
+    )?;
+
+    // Deduct tax after querying simulation
+    let mut return_tax_amount = Uint128::zero();
+    if let AssetInfo::NativeToken { denom } = ask_asset_info {
+        return_tax_amount = compute_tax(&deps.querier, res.return_amount, denom)?;
+        res.return_amount = res.return_amount.checked_sub(return_tax_amount)?;
+    }
+
+    Ok(SwapOperationSimulation {
+        pair_contract: Some(pair.address.to_string()),
+        offer_amount,
+        return_amount: res.return_amount,
+        commission_amount: res.commission_amount,
+        spread_amount: res.spread_amount,
+        offer_tax_amount,
+        return_tax_amount,
+        spot_price,
+    })
+}
+
+/// Ask denom per unit of offer denom at the oracle exchange rate
+fn query_market_spot_price(
+    terra_querier: &TerraQuerier,
+    offer_denom: String,
+    ask_denom: String,
+) -> StdResult<Decimal> {
+    let res = terra_querier.query_exchange_rates(offer_denom, vec![ask_denom])?;
+    match res.exchange_rates.first() {
+        Some(item) => Ok(item.exchange_rate),
+        None => Err(StdError::generic_err("no exchange rate")),
+    }
+}
+
+pub fn reverse_simulate_swap_operations(
+    deps: Deps,
+    router: &dyn RouterExtension,
+    ask_amount: Uint128,
+    operations: Vec<SwapOperation>,
+) -> StdResult<ReverseSimulateSwapOperationsResponse> {
+    let terraswap_factory = router.terraswap_factory(deps)?;
+    let terra_querier = TerraQuerier::new(&deps.querier);
+
+    let operations_len = operations.len();
+    if operations_len == 0 {
+        return Err(StdError::generic_err("must provide operations"));
+    }
+
+    // From the last operation to the first, the offer of an operation
+    // is the ask of the operation before it
+    let mut ask_amount = ask_amount;
+    for (operation_index, operation) in operations.into_iter().enumerate().rev() {
+        match operation {
+            SwapOperation::NativeSwap {
+                offer_denom,
+                ask_denom,
+            } => {
+                let mut offer_amount = reverse_market_swap(
+                    &terra_querier,
+                    offer_denom.clone(),
+                    ask_denom,
+                    ask_amount,
+                )?;
+
+                // Add the tax deducted from the offer of the last swap_send
+                if operation_index + 1 == operations_len {
+                    offer_amount = offer_amount.checked_add(compute_reverse_tax(
+                        &deps.querier,
+                        offer_amount,
+                        offer_denom,
+                    )?)?;
+                }
+
+                ask_amount = offer_amount;
+            }
+            SwapOperation::TerraSwap {
+                offer_asset_info,
+                ask_asset_info,
+                ..
+            } => {
+                let pair_info: PairInfo = query_pair_info(
+                    &deps.querier,
+                    terraswap_factory.clone(),
+                    &[offer_asset_info.clone(), ask_asset_info.clone()],
+                )?;
+
+                ask_amount = reverse_simulate_pair_swap(
+                    deps,
+                    &PairContract::terraswap(Addr::unchecked(pair_info.contract_addr)),
+                    offer_asset_info,
+                    ask_asset_info,
+                    ask_amount,
+                )?;
+            }
+            SwapOperation::Pool {
+                pair_address,
+                offer_asset_info,
+                ask_asset_info,
+                protocol,
+                ..
+            } => {
+                let pair = router.pool_pair(deps, &pair_address, &protocol)?;
+
+                ask_amount = reverse_simulate_pair_swap(
+                    deps,
+                    &pair,
+                    offer_asset_info,
+                    ask_asset_info,
+                    ask_amount,
+                )?;
+            }
+            _ => return Err(StdError::generic_err("not implemented")),
+        }
+    }
+
+    Ok(ReverseSimulateSwapOperationsResponse {
+        offer_amount: ask_amount,
+    })
+}
+
+/// Offer amount of a pair swap returning the ask amount after the taxes
+fn reverse_simulate_pair_swap(
+    deps: Deps,
+    pair: &PairContract,
+    offer_asset_info: AssetInfo,
+    ask_asset_info: AssetInfo,
+    ask_amount: Uint128,
+) -> StdResult<Uint128> {
+    // Add the tax deducted after the swap
+    let mut ask_amount = ask_amount;
+    if let AssetInfo::NativeToken { denom } = ask_asset_info.clone() {
+        ask_amount =
+            ask_amount.checked_add(compute_reverse_tax(&deps.querier, ask_amount, denom)?)?;
+    }
+
+    let res = pair.reverse_simulate(
+        deps,
+        Asset {
+            info: ask_asset_info,
+            amount: ask_amount,
+        },
+        offer_asset_info.clone(),
+    )?;
+
+    // The pair rounds the offer amount down,
+    // one more keeps the return at or above the ask amount
+    let mut offer_amount = res.offer_amount.checked_add(Uint128::from(1u8))?;
+
+    // Add the tax deducted before the swap
+    if let AssetInfo::NativeToken { denom } = offer_asset_info {
+        offer_amount =
+            offer_amount.checked_add(compute_reverse_tax(&deps.querier, offer_amount, denom)?)?;
+    }
+
+    Ok(offer_amount)
+}
+
+/// The market has no reverse query, so the ask amount is swapped back into
+/// the offer denom and scaled by the spread of swapping that forward again
+fn reverse_market_swap(
+    terra_querier: &TerraQuerier,
+    offer_denom: String,
+    ask_denom: String,
+    ask_amount: Uint128,
+) -> StdResult<Uint128> {
+    let backward_amount = terra_querier
+        .query_swap(
+            Coin {
+                denom: ask_denom.clone(),
+                amount: ask_amount,
+            },
+            offer_denom.clone(),
+        )?
+        .receive
+        .amount;
+    let forward_amount = terra_querier
+        .query_swap(
+            Coin {
+                denom: offer_denom,
+                amount: backward_amount,
+            },
+            ask_denom,
+        )?
+        .receive
+        .amount;
+
+    if forward_amount.is_zero() {
+        return Err(StdError::generic_err("ask amount is too small"));
+    }
+
+    Ok(backward_amount.multiply_ratio(ask_amount, forward_amount))
+}
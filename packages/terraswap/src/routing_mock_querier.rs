@@ -7,18 +7,20 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::asset::{Asset, AssetInfo, PairInfo};
+use crate::pair::{SimulationResponse, SpotPriceResponse};
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
 use terra_cosmwasm::{
-    SwapResponse, TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute,
+    ExchangeRateItem, ExchangeRatesResponse, SwapResponse, TaxCapResponse, TaxRateResponse,
+    TerraQuery, TerraQueryWrapper, TerraRoute,
 };
-use terraswap::asset::{Asset, AssetInfo, PairInfo};
-use terraswap::pair::SimulationResponse;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Pair { asset_infos: [AssetInfo; 2] },
     Simulation { offer_asset: Asset },
+    SpotPrice { base: AssetInfo },
 }
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
@@ -133,12 +135,6 @@ impl Querier for WasmMockQuerier {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum MockQueryMsg {
-    Price {},
-}
-
 impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
         match &request {
@@ -176,6 +172,27 @@ impl WasmMockQuerier {
                         }
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if route == &TerraRoute::Oracle {
+                    match query_data {
+                        TerraQuery::ExchangeRates {
+                            base_denom,
+                            quote_denoms,
+                        } => {
+                            // the market swaps one to one
+                            let res = ExchangeRatesResponse {
+                                base_denom: base_denom.to_string(),
+                                exchange_rates: quote_denoms
+                                    .iter()
+                                    .map(|quote_denom| ExchangeRateItem {
+                                        quote_denom: quote_denom.to_string(),
+                                        exchange_rate: Decimal::one(),
+                                    })
+                                    .collect(),
+                            };
+                            SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                        }
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
                 } else {
                     panic!("DO NOT ENTER HERE")
                 }
@@ -195,6 +212,7 @@ impl WasmMockQuerier {
                                     denom: "uusd".to_string(),
                                 },
                             ],
+                            asset_decimals: [6u8, 6u8],
                         }))),
                         None => SystemResult::Err(SystemError::InvalidRequest {
                             error: "No pair info exists".to_string(),
@@ -209,6 +227,14 @@ impl WasmMockQuerier {
                         spread_amount: Uint128::zero(),
                     })))
                 }
+                Ok(QueryMsg::SpotPrice { base }) => {
+                    // the pairs swap one to one
+                    SystemResult::Ok(ContractResult::from(to_binary(&SpotPriceResponse {
+                        base: base.clone(),
+                        quote: base,
+                        price: Decimal::one(),
+                    })))
+                }
                 _ => match from_binary(msg).unwrap() {
                     Cw20QueryMsg::Balance { address } => {
                         let balances: &HashMap<String, Uint128> =
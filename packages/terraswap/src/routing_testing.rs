@@ -0,0 +1,668 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, Decimal, Deps, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use terra_cosmwasm::{create_swap_msg, create_swap_send_msg};
+
+use crate::asset::{Asset, AssetInfo};
+use crate::pair::ExecuteMsg as PairExecuteMsg;
+use crate::router::{
+    ExecuteMsg, SimulateSwapOperationsResponse, SwapOperation, SwapOperationSimulation,
+};
+use crate::routing::{self, RouterExtension};
+use crate::routing_mock_querier::mock_dependencies;
+
+/// Router swapping on the pairs of the mocked factory only
+struct MockRouter;
+
+impl RouterExtension for MockRouter {
+    fn terraswap_factory(&self, _deps: Deps) -> StdResult<Addr> {
+        Ok(Addr::unchecked("terraswapfactory"))
+    }
+}
+
+#[test]
+fn execute_swap_operations() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"asset0002".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    let res = routing::execute_swap_operations(
+        deps.as_mut(),
+        mock_env(),
+        Addr::unchecked("addr0000"),
+        vec![],
+        None,
+        None,
+        None,
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "must provide operations"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let operations = vec![
+        SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "ukrw".to_string(),
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0002".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+    ];
+
+    let res = routing::execute_swap_operations(
+        deps.as_mut(),
+        mock_env(),
+        Addr::unchecked("addr0000"),
+        operations.clone(),
+        Some(Uint128::from(1000000u128)),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::NativeSwap {
+                        offer_denom: "uusd".to_string(),
+                        ask_denom: "ukrw".to_string(),
+                    },
+                    to: None,
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::TerraSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::Token {
+                            contract_addr: "asset0001".to_string(),
+                        },
+                        max_spread: None,
+                        belief_price: None,
+                    },
+                    to: None,
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::TerraSwap {
+                        offer_asset_info: AssetInfo::Token {
+                            contract_addr: "asset0001".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        max_spread: None,
+                        belief_price: None,
+                    },
+                    to: None,
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::TerraSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::Token {
+                            contract_addr: "asset0002".to_string(),
+                        },
+                        max_spread: None,
+                        belief_price: None,
+                    },
+                    to: Some("addr0000".to_string()),
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive {
+                    asset_info: AssetInfo::Token {
+                        contract_addr: "asset0002".to_string(),
+                    },
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: Uint128::from(1000000u128),
+                    receiver: "addr0000".to_string(),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+
+    // the return goes to `to` instead of the sender
+    let res = routing::execute_swap_operations(
+        deps.as_mut(),
+        mock_env(),
+        Addr::unchecked("addr0000"),
+        operations,
+        None,
+        Some(Addr::unchecked("addr0002")),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::NativeSwap {
+                        offer_denom: "uusd".to_string(),
+                        ask_denom: "ukrw".to_string(),
+                    },
+                    to: None,
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::TerraSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::Token {
+                            contract_addr: "asset0001".to_string(),
+                        },
+                        max_spread: None,
+                        belief_price: None,
+                    },
+                    to: None,
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::TerraSwap {
+                        offer_asset_info: AssetInfo::Token {
+                            contract_addr: "asset0001".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        max_spread: None,
+                        belief_price: None,
+                    },
+                    to: None,
+                    offer_amount: None,
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: SwapOperation::TerraSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::Token {
+                            contract_addr: "asset0002".to_string(),
+                        },
+                        max_spread: None,
+                        belief_price: None,
+                    },
+                    to: Some("addr0002".to_string()),
+                    offer_amount: None,
+                })
+                .unwrap(),
+            }))
+        ]
+    );
+}
+
+#[test]
+fn execute_swap_operations_invalid_routes() {
+    let mut deps = mock_dependencies(&[]);
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let uluna = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    let asset0001 = AssetInfo::Token {
+        contract_addr: "asset0001".to_string(),
+    };
+    let terraswap =
+        |offer_asset_info: &AssetInfo, ask_asset_info: &AssetInfo| SwapOperation::TerraSwap {
+            offer_asset_info: offer_asset_info.clone(),
+            ask_asset_info: ask_asset_info.clone(),
+            max_spread: None,
+            belief_price: None,
+        };
+
+    let routes = vec![
+        (
+            vec![terraswap(&uusd, &uluna), terraswap(&asset0001, &uusd)],
+            "invalid operations; operation 1 does not offer the ask asset of operation 0",
+        ),
+        (
+            vec![
+                terraswap(&uusd, &uluna),
+                terraswap(&uluna, &asset0001),
+                terraswap(&asset0001, &uluna),
+            ],
+            "invalid operations; operation 2 swaps back into uluna",
+        ),
+        (
+            vec![
+                terraswap(&uusd, &uluna),
+                SwapOperation::WormHoleBridge {
+                    asset_info: uluna.clone(),
+                    wallet_address_on_target_chain: "wallet0000".to_string(),
+                },
+            ],
+            "invalid operations; operation 1 is not a swap",
+        ),
+    ];
+
+    for (operations, error) in routes {
+        match routing::execute_swap_operations(
+            deps.as_mut(),
+            mock_env(),
+            Addr::unchecked("addr0000"),
+            operations,
+            None,
+            None,
+            None,
+        ) {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, error),
+            _ => panic!("DO NOT ENTER HERE"),
+        }
+    }
+}
+
+#[test]
+fn execute_swap_operation() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier
+        .with_terraswap_pairs(&[(&"uusdasset".to_string(), &"pair".to_string())]);
+    deps.querier.with_tax(
+        Decimal::percent(5),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+    deps.querier.with_balance(&[(
+        MOCK_CONTRACT_ADDR.to_string(),
+        &[Coin {
+            amount: Uint128::from(1000000u128),
+            denom: "uusd".to_string(),
+        }],
+    )]);
+
+    let operation = SwapOperation::NativeSwap {
+        offer_denom: "uusd".to_string(),
+        ask_denom: "uluna".to_string(),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = routing::execute_swap_operation(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        &MockRouter,
+        operation.clone(),
+        None,
+        None,
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = routing::execute_swap_operation(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        &MockRouter,
+        operation,
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(create_swap_msg(
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000000u128),
+            },
+            "uluna".to_string()
+        ))],
+    );
+
+    // optional to address
+    // swap_send
+    let operation = SwapOperation::NativeSwap {
+        offer_denom: "uusd".to_string(),
+        ask_denom: "uluna".to_string(),
+    };
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = routing::execute_swap_operation(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        &MockRouter,
+        operation,
+        Some("addr0000".to_string()),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(create_swap_send_msg(
+            "addr0000".to_string(),
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(952380u128), // deduct tax
+            },
+            "uluna".to_string()
+        ))],
+    );
+    deps.querier
+        .with_terraswap_pairs(&[(&"assetuusd".to_string(), &"pair".to_string())]);
+    deps.querier.with_token_balances(&[(
+        &"asset".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    let operation = SwapOperation::TerraSwap {
+        offer_asset_info: AssetInfo::Token {
+            contract_addr: "asset".to_string(),
+        },
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        max_spread: None,
+        belief_price: None,
+    };
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = routing::execute_swap_operation(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        &MockRouter,
+        operation,
+        Some("addr0000".to_string()),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "asset".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair".to_string(),
+                amount: Uint128::from(1000000u128),
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: "asset".to_string(),
+                        },
+                        amount: Uint128::from(1000000u128),
+                    },
+                    belief_price: None,
+                    max_spread: None,
+                    to: Some("addr0000".to_string()),
+                })
+                .unwrap()
+            })
+            .unwrap()
+        }))]
+    );
+}
+
+#[test]
+fn query_buy_with_routes() {
+    let mut deps = mock_dependencies(&[]);
+
+    // set tax rate as 5%
+    deps.querier.with_tax(
+        Decimal::percent(5),
+        &[
+            (&"uusd".to_string(), &Uint128::from(1000000u128)),
+            (&"ukrw".to_string(), &Uint128::from(1000000u128)),
+        ],
+    );
+
+    let operations = vec![
+        SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "ukrw".to_string(),
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0000".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+    ];
+
+    deps.querier.with_terraswap_pairs(&[
+        (&"ukrwasset0000".to_string(), &"pair0000".to_string()),
+        (&"asset0000uluna".to_string(), &"pair0001".to_string()),
+    ]);
+
+    let res: SimulateSwapOperationsResponse = routing::simulate_swap_operations(
+        deps.as_ref(),
+        &MockRouter,
+        Uint128::from(1000000u128),
+        operations,
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        SimulateSwapOperationsResponse {
+            amount: Uint128::from(952380u128), // tax charged 1 times uusd => ukrw, ukrw => asset0000, asset0000 => uluna
+            hops: vec![
+                SwapOperationSimulation {
+                    pair_contract: None,
+                    offer_amount: Uint128::from(1000000u128),
+                    return_amount: Uint128::from(1000000u128),
+                    commission_amount: Uint128::zero(),
+                    spread_amount: Uint128::zero(),
+                    offer_tax_amount: Uint128::zero(),
+                    return_tax_amount: Uint128::zero(),
+                    spot_price: Decimal::one(),
+                },
+                SwapOperationSimulation {
+                    pair_contract: Some("pair0000".to_string()),
+                    offer_amount: Uint128::from(1000000u128),
+                    return_amount: Uint128::from(952380u128),
+                    commission_amount: Uint128::zero(),
+                    spread_amount: Uint128::zero(),
+                    offer_tax_amount: Uint128::from(47620u128),
+                    return_tax_amount: Uint128::zero(),
+                    spot_price: Decimal::one(),
+                },
+                SwapOperationSimulation {
+                    pair_contract: Some("pair0001".to_string()),
+                    offer_amount: Uint128::from(952380u128),
+                    return_amount: Uint128::from(952380u128),
+                    commission_amount: Uint128::zero(),
+                    spread_amount: Uint128::zero(),
+                    offer_tax_amount: Uint128::zero(),
+                    return_tax_amount: Uint128::zero(),
+                    spot_price: Decimal::one(),
+                },
+            ],
+            price_impact: Decimal::from_ratio(47620u128, 1000000u128),
+        }
+    );
+
+    let operations = vec![
+        SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "ukrw".to_string(),
+        },
+        SwapOperation::NativeSwap {
+            offer_denom: "ukrw".to_string(),
+            ask_denom: "uluna".to_string(),
+        },
+    ];
+
+    let res: SimulateSwapOperationsResponse = routing::simulate_swap_operations(
+        deps.as_ref(),
+        &MockRouter,
+        Uint128::from(1000000u128),
+        operations,
+    )
+    .unwrap();
+    assert_eq!(res.amount, Uint128::from(952380u128)); // tax charged 1 times uusd => ukrw, ukrw => uluna
+    assert_eq!(res.hops[1].offer_tax_amount, Uint128::from(47620u128));
+    assert_eq!(
+        res.price_impact,
+        Decimal::from_ratio(47620u128, 1000000u128)
+    );
+}
+
+#[test]
+fn assert_minimum_receive_native_token() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance(&[(
+        "addr0000".to_string(),
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    )]);
+
+    let asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    // success
+    let _res = routing::assert_minimum_receive(
+        deps.as_ref(),
+        asset_info.clone(),
+        Uint128::zero(),
+        Uint128::from(1000000u128),
+        Addr::unchecked("addr0000"),
+    )
+    .unwrap();
+
+    // assertion failed; native token
+    let res = routing::assert_minimum_receive(
+        deps.as_ref(),
+        asset_info,
+        Uint128::zero(),
+        Uint128::from(1000001u128),
+        Addr::unchecked("addr0000"),
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "assertion failed; minimum receive amount: 1000001, swap amount: 1000000"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
+
+#[test]
+fn assert_minimum_receive_token() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"token0000".to_string(),
+        &[(&"addr0000".to_string(), &Uint128::from(1000000u128))],
+    )]);
+
+    let asset_info = AssetInfo::Token {
+        contract_addr: "token0000".to_string(),
+    };
+    // success
+    let _res = routing::assert_minimum_receive(
+        deps.as_ref(),
+        asset_info.clone(),
+        Uint128::zero(),
+        Uint128::from(1000000u128),
+        Addr::unchecked("addr0000"),
+    )
+    .unwrap();
+
+    // assertion failed; token
+    let res = routing::assert_minimum_receive(
+        deps.as_ref(),
+        asset_info,
+        Uint128::zero(),
+        Uint128::from(1000001u128),
+        Addr::unchecked("addr0000"),
+    );
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "assertion failed; minimum receive amount: 1000001, swap amount: 1000000"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
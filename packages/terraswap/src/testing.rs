@@ -1,11 +1,15 @@
-use crate::asset::{Asset, AssetInfo, PairInfo};
+use crate::asset::{native_symbol, Asset, AssetInfo, PairInfo};
 use crate::mock_querier::mock_dependencies;
 use crate::querier::{
     query_all_balances, query_balance, query_pair_info, query_supply, query_token_balance,
 };
+use crate::router::SwapOperation;
+use crate::routing::assert_operations;
 
 use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
-use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Uint128, WasmMsg};
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, StdError, Uint128, WasmMsg,
+};
 use cw20::Cw20ExecuteMsg;
 
 #[test]
@@ -150,6 +154,22 @@ fn test_asset_info() {
             .unwrap(),
         Uint128::from(123u128)
     );
+
+    assert_eq!(
+        token_info
+            .query_symbol(&deps.as_ref().querier, deps.as_ref().api)
+            .unwrap(),
+        "mAAPL".to_string()
+    );
+    assert_eq!(
+        native_token_info
+            .query_symbol(&deps.as_ref().querier, deps.as_ref().api)
+            .unwrap(),
+        "UST".to_string()
+    );
+    assert_eq!(native_symbol("uluna"), "LUNA".to_string());
+    assert_eq!(native_symbol("ukrw"), "KRT".to_string());
+    assert_eq!(native_symbol("usdr"), "SDT".to_string());
 }
 
 #[test]
@@ -255,6 +275,7 @@ fn query_terraswap_pair_contract() {
             ],
             contract_addr: "pair0000".to_string(),
             liquidity_token: "liquidity0000".to_string(),
+            asset_decimals: [6u8, 6u8],
         },
     )]);
 
@@ -275,3 +296,114 @@ fn query_terraswap_pair_contract() {
     assert_eq!(pair_info.contract_addr, Addr::unchecked("pair0000"),);
     assert_eq!(pair_info.liquidity_token, Addr::unchecked("liquidity0000"),);
 }
+
+#[test]
+fn test_invalid_operations() {
+    // empty error
+    assert!(assert_operations(&[]).is_err());
+
+    // uusd -> uluna -> asset0001 -> ukrw
+    assert!(assert_operations(&[
+        SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "uluna".to_string(),
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+    ])
+    .is_ok());
+
+    // discontinuous error
+    match assert_operations(&[
+        SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "uluna".to_string(),
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+    ]) {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+            msg,
+            "invalid operations; operation 1 does not offer the ask asset of operation 0"
+        ),
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // cyclic error
+    match assert_operations(&[
+        SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "uluna".to_string(),
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: "asset0001".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            max_spread: None,
+            belief_price: None,
+        },
+    ]) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "invalid operations; operation 2 swaps back into uluna")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    // bridge error
+    match assert_operations(&[
+        SwapOperation::NativeSwap {
+            offer_denom: "uusd".to_string(),
+            ask_denom: "uluna".to_string(),
+        },
+        SwapOperation::WormHoleBridge {
+            asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            wallet_address_on_target_chain: "wallet0000".to_string(),
+        },
+    ]) {
+        Err(StdError::GenericErr { msg, .. }) => {
+            assert_eq!(msg, "invalid operations; operation 1 is not a swap")
+        }
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+}
@@ -2,7 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{StdError, StdResult, Uint128};
-use cw20::{Cw20Coin, MinterResponse};
+use cw20::{Cw20Coin, Logo, MinterResponse};
 
 /// TokenContract InstantiateMsg
 #[derive(Serialize, Deserialize, JsonSchema)]
@@ -12,6 +12,16 @@ pub struct InstantiateMsg {
     pub decimals: u8,
     pub initial_balances: Vec<Cw20Coin>,
     pub mint: Option<MinterResponse>,
+    pub marketing: Option<InstantiateMarketingInfo>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct InstantiateMarketingInfo {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    /// Address allowed to update the marketing info and logo, immutable if not set
+    pub marketing: Option<String>,
+    pub logo: Option<Logo>,
 }
 
 impl InstantiateMsg {